@@ -0,0 +1,335 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Proleptic Gregorian calendar arithmetic.
+//!
+//! The day counting follows Howard Hinnant's `days_from_civil` and
+//! `civil_from_days` algorithms, which work in 400-year eras and are exact
+//! for every value of their `i64` arguments that does not overflow.
+
+use super::{Tm, MIN_YEAR, MAX_YEAR};
+
+pub static SECS_PER_DAY: i64 = 86_400;
+
+/// The Modified Julian Day of 1970-01-01. Day 0 of the Modified Julian Date
+/// is 1858-11-17, which starts at Julian Date 2400000.5.
+pub static UNIX_EPOCH_MJD: i64 = 40_587;
+
+/// Added to a Modified Julian Day to give the Julian Day Number, which
+/// counts noons from January 1, 4713 BC in the proleptic Julian calendar.
+pub static MJD_TO_JDN: i64 = 2_400_001;
+
+/// Added to a Modified Julian Day to give the Rata Die, which counts days
+/// from 0001-01-01, day 1.
+pub static MJD_TO_RATA_DIE: i64 = 678_576;
+
+/// Floored division, rounding towards negative infinity.
+pub fn div_floor(a: i64, b: i64) -> i64 {
+    let d = a / b;
+    if (a % b != 0) && ((a < 0) != (b < 0)) { d - 1 } else { d }
+}
+
+/// The remainder matching `div_floor`, always in `[0, b)` for positive `b`.
+pub fn mod_floor(a: i64, b: i64) -> i64 {
+    let r = a % b;
+    if (r != 0) && ((r < 0) != (b < 0)) { r + b } else { r }
+}
+
+/// Returns the number of days since 1970-01-01 of the given date. `month` is
+/// in `[1, 12]` and `day` in `[1, 31]`.
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = div_floor(y, 400);
+    let yoe = y - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Returns the `(year, month, day)` of the date `days` days after 1970-01-01,
+/// with `month` in `[1, 12]` and `day` in `[1, 31]`.
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = div_floor(z, 146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Returns the day of the week of the date `days` days after 1970-01-01,
+/// 0 being Sunday.
+pub fn weekday_from_days(days: i64) -> i64 {
+    // 1970-01-01 was a Thursday.
+    mod_floor(days + 4, 7)
+}
+
+pub fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0) && ((year % 100 != 0) || (year % 400 == 0))
+}
+
+/// Returns the number of days in `month`, in `[1, 12]`, of `year`.
+pub fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 => if is_leap_year(year) { 29 } else { 28 },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the fiscal year that month `month`, in `[1, 12]`, of `year` falls
+/// in when fiscal years start in month `start`, and the number of months of
+/// that fiscal year before `month`. Fiscal years are numbered by the calendar
+/// year they start in.
+pub fn fiscal_month(year: i64, month: i64, start: i64) -> (i64, i64) {
+    (if month >= start { year } else { year - 1 }, mod_floor(month - start, 12))
+}
+
+/// Returns the number of ISO 8601 weeks in `year`, 52 or 53. Years with 53
+/// weeks start on a Thursday, or on a Wednesday if they are leap years.
+pub fn iso_weeks_in_year(year: i64) -> i64 {
+    match weekday_from_days(days_from_civil(year, 1, 1)) {
+        4 => 53,
+        3 if is_leap_year(year) => 53,
+        _ => 52,
+    }
+}
+
+/// Returns the ISO 8601 `(year, week)` of the day `yday` days after January 1
+/// of `year`, which falls on `wday`, 0 being Sunday. Week 1 is the week, from
+/// Monday to Sunday, with the year's first Thursday.
+pub fn iso_week(year: i64, yday: i64, wday: i64) -> (i64, i64) {
+    let iso_wday = mod_floor(wday - 1, 7) + 1;
+    let week = div_floor(yday - iso_wday + 11, 7);
+    if week < 1 {
+        (year - 1, iso_weeks_in_year(year - 1))
+    } else if week > iso_weeks_in_year(year) {
+        (year + 1, 1)
+    } else {
+        (year, week)
+    }
+}
+
+/// Returns the number of days since 1970-01-01 of the day `iso_wday`, 1
+/// being Monday and 7 Sunday, of ISO 8601 week `week` of `year`.
+pub fn days_from_iso_week(year: i64, week: i64, iso_wday: i64) -> i64 {
+    // January 4 is always in week 1.
+    let jan4 = days_from_civil(year, 1, 4);
+    let monday = jan4 - mod_floor(weekday_from_days(jan4) - 1, 7);
+    monday + (week - 1) * 7 + iso_wday - 1
+}
+
+/// Returns whether `sec` seconds since the epoch falls in a UTC year in
+/// `[MIN_YEAR, MAX_YEAR]`.
+pub fn in_supported_range(sec: i64) -> bool {
+    let days = div_floor(sec, SECS_PER_DAY);
+    days >= days_from_civil(MIN_YEAR as i64, 1, 1) &&
+        days < days_from_civil(MAX_YEAR as i64 + 1, 1, 1)
+}
+
+/// Breaks down `sec` seconds since the epoch, shifted by `utcoff` seconds east
+/// of UTC, into a `Tm`. `tm_isdst` is left at zero.
+///
+/// Panics if the year is outside `[MIN_YEAR, MAX_YEAR]`.
+pub fn tm_from_seconds(sec: i64, nsec: i32, utcoff: i32) -> Tm {
+    checked_tm_from_seconds(sec, nsec, utcoff).expect("year out of the supported range")
+}
+
+/// Like `tm_from_seconds`, but returns `None` if the year is outside
+/// `[MIN_YEAR, MAX_YEAR]`.
+///
+/// The day is split off before `utcoff` is applied, so this does not overflow
+/// for any `sec`.
+pub fn checked_tm_from_seconds(sec: i64, nsec: i32, utcoff: i32) -> Option<Tm> {
+    let shifted = mod_floor(sec, SECS_PER_DAY) + utcoff as i64;
+    let days = div_floor(sec, SECS_PER_DAY) + div_floor(shifted, SECS_PER_DAY);
+    let secs_of_day = mod_floor(shifted, SECS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    if year < MIN_YEAR as i64 || year > MAX_YEAR as i64 {
+        return None;
+    }
+
+    Some(Tm {
+        tm_sec: (secs_of_day % 60) as i32,
+        tm_min: (secs_of_day / 60 % 60) as i32,
+        tm_hour: (secs_of_day / 3600) as i32,
+        tm_mday: day as i32,
+        tm_mon: (month - 1) as i32,
+        tm_year: (year - 1900) as i32,
+        tm_wday: weekday_from_days(days) as i32,
+        tm_yday: (days - days_from_civil(year, 1, 1)) as i32,
+        tm_isdst: 0,
+        tm_utcoff: utcoff,
+        tm_nsec: nsec,
+    })
+}
+
+/// Returns the number of seconds since the epoch of the wall-clock time in
+/// `tm`, as if it were in UTC. Fields outside their usual ranges are carried
+/// into the next larger unit; `tm_wday`, `tm_yday`, `tm_utcoff` and
+/// `tm_nsec` are ignored.
+pub fn local_seconds(tm: &Tm) -> i64 {
+    let month = tm.tm_mon as i64;
+    let year = tm.tm_year as i64 + 1900 + div_floor(month, 12);
+    let days = days_from_civil(year, mod_floor(month, 12) + 1, 1) + tm.tm_mday as i64 - 1;
+    days * SECS_PER_DAY + tm.tm_hour as i64 * 3600 + tm.tm_min as i64 * 60 + tm.tm_sec as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{i32, i64};
+    use super::{days_from_civil, civil_from_days, weekday_from_days, div_floor,
+                mod_floor, tm_from_seconds, checked_tm_from_seconds, in_supported_range,
+                local_seconds,
+                iso_week, iso_weeks_in_year, days_from_iso_week, fiscal_month,
+                SECS_PER_DAY};
+
+    #[test]
+    fn test_floor_division() {
+        assert_eq!(div_floor(7, 2), 3);
+        assert_eq!(div_floor(-7, 2), -4);
+        assert_eq!(div_floor(-8, 2), -4);
+        assert_eq!(mod_floor(-7, 2), 1);
+        assert_eq!(mod_floor(-1, 86_400), 86_399);
+    }
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(1, 1, 1), -719_162);
+        assert_eq!(days_from_civil(-1, 12, 31), -719_529);
+    }
+
+    #[test]
+    fn test_civil_round_trip() {
+        let mut days = -800_000;
+        while days < 800_000 {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+            days += 7;
+        }
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(weekday_from_days(0), 4);
+        assert_eq!(weekday_from_days(-1), 3);
+    }
+
+    #[test]
+    fn test_iso_week() {
+        // 2009-02-13 was a Friday in week 7, 2008-12-29 a Monday in week 1 of
+        // 2009, and 2010-01-03 a Sunday in week 53 of 2009.
+        assert_eq!(iso_week(2009, 43, 5), (2009, 7));
+        assert_eq!(iso_week(2008, 363, 1), (2009, 1));
+        assert_eq!(iso_week(2010, 2, 0), (2009, 53));
+        assert_eq!(iso_weeks_in_year(2009), 53);
+        assert_eq!(iso_weeks_in_year(2010), 52);
+        assert_eq!(iso_weeks_in_year(2004), 53);
+
+        assert_eq!(days_from_iso_week(2009, 7, 5), days_from_civil(2009, 2, 13));
+        assert_eq!(days_from_iso_week(2009, 1, 1), days_from_civil(2008, 12, 29));
+        assert_eq!(days_from_iso_week(2009, 53, 7), days_from_civil(2010, 1, 3));
+
+        let mut days = -800_000;
+        while days < 800_000 {
+            let (year, _, _) = civil_from_days(days);
+            let yday = days - days_from_civil(year, 1, 1);
+            let wday = weekday_from_days(days);
+            let (iso_year, week) = iso_week(year, yday, wday);
+            assert!(week >= 1 && week <= iso_weeks_in_year(iso_year));
+            assert_eq!(days_from_iso_week(iso_year, week, mod_floor(wday - 1, 7) + 1), days);
+            days += 3;
+        }
+    }
+
+    #[test]
+    fn test_fiscal_month() {
+        assert_eq!(fiscal_month(2026, 4, 4), (2026, 0));
+        assert_eq!(fiscal_month(2026, 3, 4), (2025, 11));
+        assert_eq!(fiscal_month(2026, 10, 4), (2026, 6));
+        assert_eq!(fiscal_month(2026, 10, 10), (2026, 0));
+        assert_eq!(fiscal_month(2026, 12, 1), (2026, 11));
+        assert_eq!(fiscal_month(2026, 1, 12), (2025, 1));
+    }
+
+    #[test]
+    fn test_tm_from_seconds() {
+        let tm = tm_from_seconds(1234567890, 54321, -28800);
+        assert_eq!(tm.tm_sec, 30);
+        assert_eq!(tm.tm_min, 31);
+        assert_eq!(tm.tm_hour, 15);
+        assert_eq!(tm.tm_mday, 13);
+        assert_eq!(tm.tm_mon, 1);
+        assert_eq!(tm.tm_year, 109);
+        assert_eq!(tm.tm_wday, 5);
+        assert_eq!(tm.tm_yday, 43);
+        assert_eq!(tm.tm_utcoff, -28800);
+        assert_eq!(tm.tm_nsec, 54321);
+
+        let tm = tm_from_seconds(-1, 0, 0);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (69, 11, 31));
+        assert_eq!((tm.tm_hour, tm.tm_min, tm.tm_sec), (23, 59, 59));
+        assert_eq!(tm.tm_yday, 364);
+    }
+
+    #[test]
+    fn test_seconds_range() {
+        // The last second of the latest supported year, and the first of the
+        // earliest.
+        let max = days_from_civil(i32::MAX as i64 + 1, 1, 1) * SECS_PER_DAY - 1;
+        let min = days_from_civil(i32::MIN as i64 + 1900, 1, 1) * SECS_PER_DAY;
+        for &sec in [max, max - 86_399, min, min + 86_399, 0, -1].iter() {
+            for &utcoff in [0, 3600, -3600].iter() {
+                let tm = tm_from_seconds(sec - utcoff as i64, 0, utcoff);
+                assert_eq!(local_seconds(&tm), sec);
+            }
+        }
+        let tm = tm_from_seconds(max, 0, 0);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (i32::MAX - 1900, 11, 31));
+        let tm = tm_from_seconds(min, 0, 0);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (i32::MIN, 0, 1));
+
+        // Later and earlier years, up to the extremes of `i64`, are rejected
+        // without overflowing.
+        assert!(checked_tm_from_seconds(max, 0, 1).is_none());
+        assert!(checked_tm_from_seconds(min, 0, -1).is_none());
+        assert!(checked_tm_from_seconds(i64::MAX, 0, 86_399).is_none());
+        assert!(checked_tm_from_seconds(i64::MIN, 0, -86_399).is_none());
+        assert!(checked_tm_from_seconds(max, 0, 0).is_some());
+        assert!(in_supported_range(max) && in_supported_range(min));
+        assert!(!in_supported_range(max + 1) && !in_supported_range(min - 1));
+        assert!(!in_supported_range(i64::MAX) && !in_supported_range(i64::MIN));
+    }
+
+    #[test]
+    fn test_local_seconds() {
+        let tm = tm_from_seconds(1234567890, 54321, -28800);
+        assert_eq!(local_seconds(&tm), 1234567890 - 28800);
+
+        // 2009-01-45 and 2009-14-01 carry into February and the next year.
+        let mut tm = tm_from_seconds(1234567890, 0, 0);
+        tm.tm_mon = 0;
+        tm.tm_mday = 44;
+        assert_eq!(local_seconds(&tm), 1234567890);
+        tm.tm_mon = 13;
+        tm.tm_mday = 13;
+        tm.tm_year = 108;
+        assert_eq!(local_seconds(&tm), 1234567890);
+        tm.tm_mon = -11;
+        tm.tm_year = 110;
+        assert_eq!(local_seconds(&tm), 1234567890);
+    }
+}
@@ -0,0 +1,532 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Calendar dates that are known to exist.
+
+use std::cmp;
+use std::fmt;
+use std::num::Int;
+
+use super::{Tm, IsoWeek, Month, MIN_YEAR, MAX_YEAR, ParseError, Weekday, WEEKDAYS,
+            empty_tm};
+use super::ParseError::{InvalidYear, InvalidMonth, InvalidDayOfMonth, InvalidDayOfYear};
+use calendar;
+
+/// Returns whether `year` is a leap year in the proleptic Gregorian calendar.
+pub fn is_leap_year(year: i32) -> bool {
+    calendar::is_leap_year(year as i64)
+}
+
+/// Returns the number of days in `year`, 365 or 366.
+pub fn days_in_year(year: i32) -> i32 {
+    if is_leap_year(year) { 366 } else { 365 }
+}
+
+/// Returns the number of days in `month` of `year`, or `None` if `month` is
+/// not in `[1, 12]`.
+pub fn days_in_month(year: i32, month: i32) -> Option<i32> {
+    if month >= 1 && month <= 12 {
+        Some(calendar::days_in_month(year as i64, month as i64) as i32)
+    } else {
+        None
+    }
+}
+
+/// What month arithmetic does when it lands on a day that does not exist in
+/// the target month, such as January 31 plus one month.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum DayOverflow {
+    /// Use the last day of the target month: January 31 plus one month is
+    /// February 28, or 29 in a leap year.
+    Clamp,
+    /// Carry the excess days into the following month: January 31 plus one
+    /// month is March 3, or March 2 in a leap year.
+    Overflow,
+}
+
+/// Moves the date `year`, `month` in `[1, 12]`, `day` by `months` months.
+/// The returned day may be past the end of the returned month if `policy` is
+/// `Overflow`.
+pub fn shift_months(year: i64, month: i64, day: i64, months: i64,
+                    policy: DayOverflow) -> (i64, i64, i64) {
+    let months = year * 12 + month - 1 + months;
+    let year = calendar::div_floor(months, 12);
+    let month = calendar::mod_floor(months, 12) + 1;
+    let day = match policy {
+        DayOverflow::Clamp => cmp::min(day, calendar::days_in_month(year, month)),
+        DayOverflow::Overflow => day,
+    };
+    (year, month, day)
+}
+
+/// A calendar difference between two dates, in years, months and days.
+///
+/// All fields have the same sign. `months` is in `[-11, 11]` and `days` is
+/// less than a month.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Show)]
+pub struct Period {
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+}
+
+/// Returns the calendar difference from the date `from` to the date `to`,
+/// both as `(year, month in [1, 12], day)`.
+///
+/// The whole months are counted first, as the most months that can be added
+/// to `from` with `DayOverflow::Clamp` without passing `to`, and the remaining
+/// days after them. So January 31 to February 28 is one month, as January 31
+/// plus one month is February 28. If `to` is before `from`, the result is the
+/// negated difference from `to` to `from`.
+pub fn period_between(from: (i64, i64, i64), to: (i64, i64, i64)) -> Period {
+    let (from_days, to_days) = (calendar::days_from_civil(from.0, from.1, from.2),
+                                calendar::days_from_civil(to.0, to.1, to.2));
+    if to_days < from_days {
+        let period = period_between(to, from);
+        return Period { years: -period.years, months: -period.months, days: -period.days };
+    }
+
+    let shifted = |months: i64| {
+        let (year, month, day) = shift_months(from.0, from.1, from.2, months,
+                                              DayOverflow::Clamp);
+        calendar::days_from_civil(year, month, day)
+    };
+    let mut months = (to.0 * 12 + to.1) - (from.0 * 12 + from.1);
+    if shifted(months) > to_days {
+        months -= 1;
+    }
+    Period {
+        years: months / 12,
+        months: months % 12,
+        days: to_days - shifted(months),
+    }
+}
+
+/// A date in the proleptic Gregorian calendar.
+///
+/// Unlike the fields of a `Tm`, a `Date` always names a day that exists: the
+/// month is in `[1, 12]` and the day is within the month. The year is in
+/// `[MIN_YEAR, MAX_YEAR]`, like that of a `Tm`. Dates are ordered
+/// chronologically.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Show)]
+pub struct Date {
+    year: i32,
+    month: i32,
+    day: i32,
+}
+
+impl Date {
+    /// Returns the date `day` `month` `year`, with `month` in `[1, 12]` and
+    /// `day` starting at 1, or an error naming the first field that is out
+    /// of range.
+    pub fn from_ymd(year: i32, month: i32, day: i32) -> Result<Date, ParseError> {
+        if year < MIN_YEAR {
+            return Err(InvalidYear);
+        }
+        if month < 1 || month > 12 {
+            return Err(InvalidMonth);
+        }
+        if day < 1 || day as i64 > calendar::days_in_month(year as i64, month as i64) {
+            return Err(InvalidDayOfMonth);
+        }
+        Ok(Date { year: year, month: month, day: day })
+    }
+
+    /// Returns day `ordinal` of `year`, starting at 1 for January 1, or an
+    /// error if the year has fewer days.
+    pub fn from_ordinal(year: i32, ordinal: i32) -> Result<Date, ParseError> {
+        if year < MIN_YEAR {
+            return Err(InvalidYear);
+        }
+        if ordinal < 1 || ordinal > days_in_year(year) {
+            return Err(InvalidDayOfYear);
+        }
+        let days = calendar::days_from_civil(year as i64, 1, 1) + ordinal as i64 - 1;
+        Date::from_days_since_epoch(days).ok_or(InvalidYear)
+    }
+
+    /// Returns the date held by the `tm_year`, `tm_mon` and `tm_mday` fields
+    /// of `tm`. The other fields are ignored.
+    pub fn from_tm(tm: &Tm) -> Result<Date, ParseError> {
+        match tm.tm_year.checked_add(1900) {
+            Some(year) => Date::from_ymd(year, tm.tm_mon + 1, tm.tm_mday),
+            None => Err(InvalidYear),
+        }
+    }
+
+    /// Returns the date `days` days after 1970-01-01, if its year is in range.
+    pub fn from_days_since_epoch(days: i64) -> Option<Date> {
+        let (year, month, day) = calendar::civil_from_days(days);
+        if year < MIN_YEAR as i64 || year > MAX_YEAR as i64 {
+            return None;
+        }
+        Some(Date { year: year as i32, month: month as i32, day: day as i32 })
+    }
+
+    /// Returns the date of `weekday` in week `week` of the ISO year `year`,
+    /// such as 2026-10-16 for 2026-W42-5.
+    pub fn from_iso_week_date(year: i32, week: i32, weekday: Weekday)
+                              -> Result<Date, ParseError> {
+        try!(IsoWeek::new(year, week)).day(weekday)
+    }
+
+    /// Returns the date whose noon starts the Julian Day Number `jdn`, if
+    /// its year is in range. 2000-01-01 is day 2451545.
+    pub fn from_julian_day_number(jdn: i64) -> Option<Date> {
+        Date::from_modified_julian_day(jdn - calendar::MJD_TO_JDN)
+    }
+
+    /// Returns the date of the Modified Julian Day `mjd`, if its year is in
+    /// range. 1858-11-17 is day 0.
+    pub fn from_modified_julian_day(mjd: i64) -> Option<Date> {
+        Date::from_days_since_epoch(mjd - calendar::UNIX_EPOCH_MJD)
+    }
+
+    /// Returns the date of the Rata Die `rd`, if its year is in range.
+    /// 0001-01-01 is day 1.
+    pub fn from_rata_die(rd: i64) -> Option<Date> {
+        Date::from_modified_julian_day(rd - calendar::MJD_TO_RATA_DIE)
+    }
+
+    /// Adds `months` months, going back for negative `months`, and resolves
+    /// days past the end of the target month according to `policy`. Returns
+    /// `None` if the year goes out of range.
+    pub fn add_months(&self, months: i32, policy: DayOverflow) -> Option<Date> {
+        self.shift_months(months as i64, policy)
+    }
+
+    /// Subtracts `months` months, resolving days past the end of the target
+    /// month according to `policy`.
+    pub fn sub_months(&self, months: i32, policy: DayOverflow) -> Option<Date> {
+        self.shift_months(-(months as i64), policy)
+    }
+
+    /// Adds `years` years, going back for negative `years`. `policy` decides
+    /// whether February 29 becomes February 28 or March 1 in common years.
+    pub fn add_years(&self, years: i32, policy: DayOverflow) -> Option<Date> {
+        self.shift_months(years as i64 * 12, policy)
+    }
+
+    /// Subtracts `years` years. `policy` decides whether February 29 becomes
+    /// February 28 or March 1 in common years.
+    pub fn sub_years(&self, years: i32, policy: DayOverflow) -> Option<Date> {
+        self.shift_months(-(years as i64) * 12, policy)
+    }
+
+    /// Returns the number of days from this date to `end`, negative if `end`
+    /// is earlier.
+    pub fn days_until(&self, end: Date) -> i64 {
+        end.days_since_epoch() - self.days_since_epoch()
+    }
+
+    /// Returns the calendar difference from this date to `end`, such as 1
+    /// month and 1 day from January 31 to March 1.
+    pub fn period_until(&self, end: Date) -> Period {
+        period_between((self.year as i64, self.month as i64, self.day as i64),
+                       (end.year as i64, end.month as i64, end.day as i64))
+    }
+
+    fn shift_months(&self, months: i64, policy: DayOverflow) -> Option<Date> {
+        let (year, month, day) = shift_months(self.year as i64, self.month as i64,
+                                              self.day as i64, months, policy);
+        Date::from_days_since_epoch(calendar::days_from_civil(year, month, 1) + day - 1)
+    }
+
+    /// Returns the year, such as 2015.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month, in `[1, 12]`.
+    pub fn month(&self) -> i32 {
+        self.month
+    }
+
+    /// Returns the day of the month, starting at 1.
+    pub fn day(&self) -> i32 {
+        self.day
+    }
+
+    /// Returns the day of the week.
+    pub fn weekday(&self) -> Weekday {
+        WEEKDAYS[calendar::weekday_from_days(self.days_since_epoch()) as usize]
+    }
+
+    /// Returns the day of the year, in `[1, 366]`. This is one more than
+    /// `Tm::tm_yday`.
+    pub fn ordinal(&self) -> i32 {
+        let first = calendar::days_from_civil(self.year as i64, 1, 1);
+        (self.days_since_epoch() - first) as i32 + 1
+    }
+
+    /// Returns the quarter of the year, in `[1, 4]`, with January to March
+    /// being the first.
+    pub fn quarter(&self) -> i32 {
+        (self.month - 1) / 3 + 1
+    }
+
+    /// Returns the fiscal year this date falls in when fiscal years start on
+    /// the first day of `start`.
+    ///
+    /// A fiscal year is numbered by the calendar year it starts in, so with
+    /// fiscal years starting in April, fiscal year 2026 runs from 2026-04-01
+    /// to 2027-03-31. Add one for the convention of numbering fiscal years
+    /// by the calendar year they end in. With a January start, the fiscal
+    /// year is the calendar year.
+    pub fn fiscal_year(&self, start: Month) -> i32 {
+        let (year, _) = calendar::fiscal_month(self.year as i64, self.month as i64,
+                                               start.number() as i64);
+        year as i32
+    }
+
+    /// Returns the quarter, in `[1, 4]`, of the fiscal year starting in
+    /// `start` that this date falls in. The first quarter is made of `start`
+    /// and the two months after it.
+    pub fn fiscal_quarter(&self, start: Month) -> i32 {
+        let (_, months) = calendar::fiscal_month(self.year as i64, self.month as i64,
+                                                 start.number() as i64);
+        months as i32 / 3 + 1
+    }
+
+    /// Returns the ISO 8601 week this date falls in, whose year may differ
+    /// from `year()` near January 1. This is `None` for the last days of
+    /// `MAX_YEAR` if they belong to an ISO year after it.
+    pub fn iso_week(&self) -> Option<IsoWeek> {
+        IsoWeek::of(*self)
+    }
+
+    /// Returns the number of days from 1970-01-01 to this date, negative for
+    /// earlier dates.
+    pub fn days_since_epoch(&self) -> i64 {
+        calendar::days_from_civil(self.year as i64, self.month as i64, self.day as i64)
+    }
+
+    /// Returns the Julian Day Number of the Julian day that starts at noon
+    /// UTC on this date.
+    pub fn julian_day_number(&self) -> i64 {
+        self.modified_julian_day() + calendar::MJD_TO_JDN
+    }
+
+    /// Returns the Modified Julian Day, the number of days since 1858-11-17.
+    pub fn modified_julian_day(&self) -> i64 {
+        self.days_since_epoch() + calendar::UNIX_EPOCH_MJD
+    }
+
+    /// Returns the Rata Die, the number of days since 0000-12-31, so that
+    /// 0001-01-01 is day 1.
+    pub fn rata_die(&self) -> i64 {
+        self.modified_julian_day() + calendar::MJD_TO_RATA_DIE
+    }
+
+    /// Returns midnight at the start of this date, in UTC. `tm_wday` and
+    /// `tm_yday` are filled in.
+    pub fn to_tm(&self) -> Tm {
+        Tm {
+            tm_mday: self.day,
+            tm_mon: self.month - 1,
+            tm_year: self.year - 1900,
+            tm_wday: self.weekday().number(),
+            tm_yday: self.ordinal() - 1,
+            ..empty_tm()
+        }
+    }
+}
+
+impl fmt::String for Date {
+    /// Writes the date as in ISO 8601, such as "2015-01-31".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.year < 0 {
+            write!(f, "-{:04}-{:02}-{:02}", -(self.year as i64), self.month, self.day)
+        } else {
+            write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::i32;
+    use super::{Date, Period, is_leap_year, days_in_year, days_in_month};
+    use super::DayOverflow::{Clamp, Overflow};
+    use {Timespec, empty_tm, at_utc};
+    use Weekday::{Wednesday, Friday};
+    use Month::{January, April, October};
+    use ParseError::{InvalidYear, InvalidMonth, InvalidDayOfMonth, InvalidDayOfYear};
+
+    #[test]
+    fn test_from_ymd() {
+        let date = Date::from_ymd(2009, 2, 13).unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2009, 2, 13));
+
+        assert!(Date::from_ymd(2012, 2, 29).is_ok());
+        assert!(Date::from_ymd(2000, 2, 29).is_ok());
+        assert_eq!(Date::from_ymd(1900, 2, 29), Err(InvalidDayOfMonth));
+        assert_eq!(Date::from_ymd(2009, 4, 31), Err(InvalidDayOfMonth));
+        assert_eq!(Date::from_ymd(2009, 1, 0), Err(InvalidDayOfMonth));
+        assert_eq!(Date::from_ymd(2009, 0, 1), Err(InvalidMonth));
+        assert_eq!(Date::from_ymd(2009, 13, 1), Err(InvalidMonth));
+        assert_eq!(Date::from_ymd(i32::MIN, 1, 1), Err(InvalidYear));
+        assert!(Date::from_ymd(i32::MAX, 12, 31).is_ok());
+    }
+
+    #[test]
+    fn test_weekday_ordinal() {
+        let date = Date::from_ymd(2009, 2, 13).unwrap();
+        assert_eq!(date.weekday(), Friday);
+        assert_eq!(date.ordinal(), 44);
+        assert_eq!(Date::from_ymd(2008, 12, 31).unwrap().ordinal(), 366);
+        assert_eq!(Date::from_ymd(1969, 12, 31).unwrap().weekday(), Wednesday);
+        assert_eq!(Date::from_ymd(1969, 12, 31).unwrap().days_since_epoch(), -1);
+        assert_eq!(Date::from_days_since_epoch(14288), Date::from_ymd(2009, 2, 13).ok());
+        assert_eq!(Date::from_days_since_epoch(1 << 40), None);
+    }
+
+    #[test]
+    fn test_quarters() {
+        let date = |y, m, d| Date::from_ymd(y, m, d).unwrap();
+
+        assert_eq!(date(2026, 1, 1).quarter(), 1);
+        assert_eq!(date(2026, 3, 31).quarter(), 1);
+        assert_eq!(date(2026, 4, 1).quarter(), 2);
+        assert_eq!(date(2026, 12, 31).quarter(), 4);
+
+        // With an April start, 2026-04-01 to 2027-03-31 is fiscal year 2026.
+        assert_eq!(date(2026, 4, 1).fiscal_year(April), 2026);
+        assert_eq!(date(2027, 3, 31).fiscal_year(April), 2026);
+        assert_eq!(date(2026, 3, 31).fiscal_year(April), 2025);
+        assert_eq!(date(2026, 4, 1).fiscal_quarter(April), 1);
+        assert_eq!(date(2026, 10, 17).fiscal_quarter(April), 3);
+        assert_eq!(date(2027, 1, 1).fiscal_quarter(April), 4);
+        assert_eq!(date(2027, 3, 31).fiscal_quarter(April), 4);
+
+        assert_eq!(date(2026, 9, 30).fiscal_year(October), 2025);
+        assert_eq!(date(2026, 10, 1).fiscal_quarter(October), 1);
+        assert_eq!(date(2026, 10, 17).fiscal_year(January), 2026);
+        assert_eq!(date(2026, 10, 17).fiscal_quarter(January), 4);
+    }
+
+    #[test]
+    fn test_day_counts() {
+        let date = Date::from_ymd(2000, 1, 1).unwrap();
+        assert_eq!(date.julian_day_number(), 2451545);
+        assert_eq!(date.modified_julian_day(), 51544);
+        assert_eq!(date.rata_die(), 730120);
+        assert_eq!(Date::from_julian_day_number(2451545), Some(date));
+        assert_eq!(Date::from_modified_julian_day(51544), Some(date));
+        assert_eq!(Date::from_rata_die(730120), Some(date));
+
+        assert_eq!(Date::from_modified_julian_day(0), Date::from_ymd(1858, 11, 17).ok());
+        assert_eq!(Date::from_rata_die(1), Date::from_ymd(1, 1, 1).ok());
+        assert_eq!(Date::from_julian_day_number(0), Date::from_ymd(-4713, 11, 24).ok());
+        assert_eq!(Date::from_ymd(2009, 2, 13).unwrap().rata_die(), 733451);
+        assert_eq!(Date::from_rata_die(1 << 40), None);
+    }
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(Date::from_ordinal(2009, 44), Date::from_ymd(2009, 2, 13));
+        assert_eq!(Date::from_ordinal(2009, 1), Date::from_ymd(2009, 1, 1));
+        assert_eq!(Date::from_ordinal(2012, 60), Date::from_ymd(2012, 2, 29));
+        assert_eq!(Date::from_ordinal(2012, 366), Date::from_ymd(2012, 12, 31));
+        assert_eq!(Date::from_ordinal(2009, 366), Err(InvalidDayOfYear));
+        assert_eq!(Date::from_ordinal(2009, 0), Err(InvalidDayOfYear));
+        assert_eq!(Date::from_ordinal(i32::MIN, 1), Err(InvalidYear));
+        for ordinal in 1..367 {
+            assert_eq!(Date::from_ordinal(2012, ordinal).unwrap().ordinal(), ordinal);
+        }
+
+        assert!(is_leap_year(2012) && is_leap_year(2000) && is_leap_year(-4));
+        assert!(!is_leap_year(1900) && !is_leap_year(2009));
+        assert_eq!((days_in_year(2012), days_in_year(1900)), (366, 365));
+        assert_eq!(days_in_month(2012, 2), Some(29));
+        assert_eq!(days_in_month(1900, 2), Some(28));
+        assert_eq!(days_in_month(2009, 4), Some(30));
+        assert_eq!(days_in_month(2009, 12), Some(31));
+        assert_eq!(days_in_month(2009, 13), None);
+        assert_eq!(days_in_month(2009, 0), None);
+    }
+
+    #[test]
+    fn test_tm_conversions() {
+        let tm = at_utc(Timespec::new(1234567890, 54321));
+        let date = Date::from_tm(&tm).unwrap();
+        assert_eq!(date, Date::from_ymd(2009, 2, 13).unwrap());
+        assert_eq!(date.to_tm(), at_utc(Timespec::new(1234483200, 0)));
+
+        let mut tm = empty_tm();
+        assert_eq!(Date::from_tm(&tm), Err(InvalidDayOfMonth));
+        tm.tm_mday = 1;
+        assert_eq!(Date::from_tm(&tm), Date::from_ymd(1900, 1, 1));
+        tm.tm_year = i32::MAX;
+        assert_eq!(Date::from_tm(&tm), Err(InvalidYear));
+        tm.tm_year = i32::MIN;
+        assert_eq!(Date::from_tm(&tm), Ok(Date::from_ymd(i32::MIN + 1900, 1, 1).unwrap()));
+        assert_eq!(Date::from_tm(&tm).unwrap().to_tm().tm_year, i32::MIN);
+    }
+
+    #[test]
+    fn test_add_months() {
+        let date = |y, m, d| Date::from_ymd(y, m, d).unwrap();
+
+        assert_eq!(date(2015, 1, 31).add_months(1, Clamp), Some(date(2015, 2, 28)));
+        assert_eq!(date(2016, 1, 31).add_months(1, Clamp), Some(date(2016, 2, 29)));
+        assert_eq!(date(2015, 1, 31).add_months(1, Overflow), Some(date(2015, 3, 3)));
+        assert_eq!(date(2016, 1, 31).add_months(1, Overflow), Some(date(2016, 3, 2)));
+        assert_eq!(date(2015, 1, 15).add_months(1, Overflow), Some(date(2015, 2, 15)));
+        assert_eq!(date(2015, 11, 30).add_months(3, Clamp), Some(date(2016, 2, 29)));
+        assert_eq!(date(2015, 3, 31).add_months(-1, Clamp), Some(date(2015, 2, 28)));
+        assert_eq!(date(2015, 3, 31).sub_months(13, Clamp), Some(date(2014, 2, 28)));
+        assert_eq!(date(2015, 1, 1).sub_months(1, Clamp), Some(date(2014, 12, 1)));
+        assert_eq!(date(i32::MAX, 12, 1).add_months(1, Clamp), None);
+    }
+
+    #[test]
+    fn test_add_years() {
+        let date = |y, m, d| Date::from_ymd(y, m, d).unwrap();
+
+        assert_eq!(date(2016, 2, 29).add_years(1, Clamp), Some(date(2017, 2, 28)));
+        assert_eq!(date(2016, 2, 29).add_years(1, Overflow), Some(date(2017, 3, 1)));
+        assert_eq!(date(2016, 2, 29).add_years(4, Overflow), Some(date(2020, 2, 29)));
+        assert_eq!(date(2016, 2, 29).sub_years(100, Clamp), Some(date(1916, 2, 29)));
+        assert_eq!(date(2000, 2, 29).add_years(-100, Clamp), Some(date(1900, 2, 28)));
+        assert_eq!(date(2015, 6, 1).add_years(i32::MAX, Clamp), None);
+    }
+
+    #[test]
+    fn test_period() {
+        let date = |y, m, d| Date::from_ymd(y, m, d).unwrap();
+        let period = |y, m, d| Period { years: y, months: m, days: d };
+
+        assert_eq!(date(2015, 1, 31).period_until(date(2015, 3, 1)), period(0, 1, 1));
+        // Whole months agree with `add_months` and `DayOverflow::Clamp`.
+        assert_eq!(date(2015, 1, 31).period_until(date(2015, 2, 28)), period(0, 1, 0));
+        assert_eq!(date(2015, 1, 31).add_months(1, Clamp), Some(date(2015, 2, 28)));
+        assert_eq!(date(2015, 1, 31).period_until(date(2015, 2, 27)), period(0, 0, 27));
+        assert_eq!(date(2016, 1, 31).period_until(date(2016, 2, 29)), period(0, 1, 0));
+        assert_eq!(date(2015, 2, 28).period_until(date(2015, 1, 31)), period(0, -1, 0));
+        assert_eq!(date(2012, 2, 29).period_until(date(2016, 2, 28)), period(3, 11, 30));
+        assert_eq!(date(2012, 2, 29).period_until(date(2016, 2, 29)), period(4, 0, 0));
+        assert_eq!(date(2009, 2, 13).period_until(date(2026, 10, 17)), period(17, 8, 4));
+        assert_eq!(date(2015, 3, 1).period_until(date(2015, 1, 31)), period(0, -1, -1));
+        assert_eq!(date(2015, 3, 1).period_until(date(2015, 3, 1)), period(0, 0, 0));
+
+        assert_eq!(date(2008, 12, 31).days_until(date(2009, 3, 1)), 60);
+        assert_eq!(date(2009, 3, 1).days_until(date(2008, 12, 31)), -60);
+    }
+
+    #[test]
+    fn test_ord_fmt() {
+        let a = Date::from_ymd(2008, 12, 31).unwrap();
+        let b = Date::from_ymd(2009, 1, 1).unwrap();
+        assert!(a < b);
+        assert_eq!(a.to_string(), "2008-12-31".to_string());
+        assert_eq!(Date::from_ymd(-44, 3, 15).unwrap().to_string(), "-0044-03-15".to_string());
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Dates with a time of day, with or without a UTC offset.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::num::Int;
+use std::ops::{Add, Sub};
+use std::time::Duration;
+
+use super::{Timespec, Tm, TmFmt, Fmt, ParseError, Date, Time, validate_format};
+use super::Fmt::{FmtStr, FmtRfc3339};
+use super::ParseError::InvalidZoneOffset;
+use calendar::{self, SECS_PER_DAY};
+
+/// A date and a time of day, without a time zone.
+///
+/// The fields are known to be valid, as for `Date` and `Time`. Values are
+/// ordered chronologically.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Show)]
+pub struct NaiveDateTime {
+    date: Date,
+    time: Time,
+}
+
+impl NaiveDateTime {
+    /// Combines a date and a time of day.
+    pub fn new(date: Date, time: Time) -> NaiveDateTime {
+        NaiveDateTime { date: date, time: time }
+    }
+
+    /// Returns the date and time held by `tm`, ignoring `tm_wday`, `tm_yday`,
+    /// `tm_isdst` and `tm_utcoff`. Fails if a field is out of range,
+    /// including for a leap second.
+    pub fn from_tm(tm: &Tm) -> Result<NaiveDateTime, ParseError> {
+        let date = try!(Date::from_tm(tm));
+        let time = try!(Time::from_tm(tm));
+        Ok(NaiveDateTime::new(date, time))
+    }
+
+    /// Returns the date and time in UTC of `clock`, or `None` if its year is
+    /// out of range.
+    pub fn from_timespec(clock: Timespec) -> Option<NaiveDateTime> {
+        let days = calendar::div_floor(clock.sec, SECS_PER_DAY);
+        let sec = calendar::mod_floor(clock.sec, SECS_PER_DAY) as i32;
+        Date::from_days_since_epoch(days).map(|date| {
+            let time = Time::from_hms_nano(sec / 3600, sec / 60 % 60, sec % 60, clock.nsec);
+            NaiveDateTime::new(date, time.unwrap())
+        })
+    }
+
+    /// Returns the date.
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    /// Returns the time of day.
+    pub fn time(&self) -> Time {
+        self.time
+    }
+
+    /// Returns the broken-down time, in UTC, with `tm_wday` and `tm_yday`
+    /// filled in.
+    pub fn to_tm(&self) -> Tm {
+        Tm {
+            tm_sec: self.time.second(),
+            tm_min: self.time.minute(),
+            tm_hour: self.time.hour(),
+            tm_nsec: self.time.nanosecond(),
+            ..self.date.to_tm()
+        }
+    }
+
+    /// Reads the date and time as UTC and converts it to the seconds from
+    /// January 1, 1970.
+    pub fn to_timespec(&self) -> Timespec {
+        let sec = self.date.days_since_epoch() * SECS_PER_DAY +
+                  self.time.seconds_from_midnight() as i64;
+        Timespec::new(sec, self.time.nanosecond())
+    }
+
+    /// Formats the date and time according to the format string, as for a
+    /// `Tm` in UTC.
+    pub fn strftime<'a>(&self, format: &'a str) -> Result<DateTimeFmt<'a>, ParseError> {
+        DateTimeFmt::new(self.to_tm(), FmtStr(format))
+    }
+}
+
+impl Add<Duration> for NaiveDateTime {
+    type Output = NaiveDateTime;
+
+    /// Panics if the result is out of range.
+    fn add(self, other: Duration) -> NaiveDateTime {
+        let (time, days) = self.time.overflowing_add(other);
+        let days = self.date.days_since_epoch() + days;
+        let date = Date::from_days_since_epoch(days)
+                       .expect("`NaiveDateTime + Duration` overflowed");
+        NaiveDateTime::new(date, time)
+    }
+}
+
+impl Sub<Duration> for NaiveDateTime {
+    type Output = NaiveDateTime;
+
+    /// Panics if the result is out of range.
+    fn sub(self, other: Duration) -> NaiveDateTime {
+        self + -other
+    }
+}
+
+impl Sub<NaiveDateTime> for NaiveDateTime {
+    type Output = Duration;
+
+    fn sub(self, other: NaiveDateTime) -> Duration {
+        self.to_timespec() - other.to_timespec()
+    }
+}
+
+impl fmt::String for NaiveDateTime {
+    /// Writes the date and time as in ISO 8601, such as
+    /// "2015-01-31T09:30:00".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}T{}", self.date, self.time)
+    }
+}
+
+/// A date and a time of day at a fixed offset from UTC.
+///
+/// Values are ordered by the instant they name, and values naming the same
+/// instant by their offset.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Show)]
+pub struct OffsetDateTime {
+    datetime: NaiveDateTime,
+    utcoff: i32,
+}
+
+impl OffsetDateTime {
+    /// Returns the local date and time `datetime` at `utcoff` seconds east of
+    /// UTC. Fails if the offset is a day or more.
+    pub fn new(datetime: NaiveDateTime, utcoff: i32) -> Result<OffsetDateTime, ParseError> {
+        if utcoff <= -86_400 || utcoff >= 86_400 {
+            return Err(InvalidZoneOffset);
+        }
+        Ok(OffsetDateTime { datetime: datetime, utcoff: utcoff })
+    }
+
+    /// Returns the date and time held by `tm`, at its `tm_utcoff`.
+    /// `tm_wday`, `tm_yday` and `tm_isdst` are ignored.
+    pub fn from_tm(tm: &Tm) -> Result<OffsetDateTime, ParseError> {
+        let datetime = try!(NaiveDateTime::from_tm(tm));
+        OffsetDateTime::new(datetime, tm.tm_utcoff)
+    }
+
+    /// Returns `clock` at `utcoff` seconds east of UTC, or `None` if the
+    /// offset or the resulting year is out of range.
+    pub fn from_timespec(clock: Timespec, utcoff: i32) -> Option<OffsetDateTime> {
+        clock.sec.checked_add(utcoff as i64)
+            .and_then(|sec| NaiveDateTime::from_timespec(Timespec::new(sec, clock.nsec)))
+            .and_then(|datetime| OffsetDateTime::new(datetime, utcoff).ok())
+    }
+
+    /// Returns the local date and time.
+    pub fn naive(&self) -> NaiveDateTime {
+        self.datetime
+    }
+
+    /// Returns the local date.
+    pub fn date(&self) -> Date {
+        self.datetime.date
+    }
+
+    /// Returns the local time of day.
+    pub fn time(&self) -> Time {
+        self.datetime.time
+    }
+
+    /// Returns the offset in seconds east of UTC.
+    pub fn utcoff(&self) -> i32 {
+        self.utcoff
+    }
+
+    /// Returns the broken-down time, with `tm_utcoff` set and `tm_wday` and
+    /// `tm_yday` filled in. `tm_isdst` is 0.
+    pub fn to_tm(&self) -> Tm {
+        Tm { tm_utcoff: self.utcoff, ..self.datetime.to_tm() }
+    }
+
+    /// Converts the time to the seconds from January 1, 1970.
+    pub fn to_timespec(&self) -> Timespec {
+        let local = self.datetime.to_timespec();
+        Timespec::new(local.sec - self.utcoff as i64, local.nsec)
+    }
+
+    /// Formats the date and time according to the format string.
+    pub fn strftime<'a>(&self, format: &'a str) -> Result<DateTimeFmt<'a>, ParseError> {
+        DateTimeFmt::new(self.to_tm(), FmtStr(format))
+    }
+
+    /// Returns a DateTimeFmt that outputs according to RFC 3339, as
+    /// `Tm::rfc3339` does.
+    pub fn rfc3339(&self) -> DateTimeFmt<'static> {
+        DateTimeFmt { tm: self.to_tm(), format: FmtRfc3339 }
+    }
+}
+
+impl PartialOrd for OffsetDateTime {
+    fn partial_cmp(&self, other: &OffsetDateTime) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OffsetDateTime {
+    fn cmp(&self, other: &OffsetDateTime) -> Ordering {
+        match self.to_timespec().cmp(&other.to_timespec()) {
+            Ordering::Equal => self.utcoff.cmp(&other.utcoff),
+            ordering => ordering,
+        }
+    }
+}
+
+impl Add<Duration> for OffsetDateTime {
+    type Output = OffsetDateTime;
+
+    /// The result has the same UTC offset as `self`. Panics if the result is
+    /// out of range.
+    fn add(self, other: Duration) -> OffsetDateTime {
+        OffsetDateTime { datetime: self.datetime + other, utcoff: self.utcoff }
+    }
+}
+
+impl Sub<Duration> for OffsetDateTime {
+    type Output = OffsetDateTime;
+
+    /// The result has the same UTC offset as `self`. Panics if the result is
+    /// out of range.
+    fn sub(self, other: Duration) -> OffsetDateTime {
+        OffsetDateTime { datetime: self.datetime - other, utcoff: self.utcoff }
+    }
+}
+
+impl Sub<OffsetDateTime> for OffsetDateTime {
+    type Output = Duration;
+
+    fn sub(self, other: OffsetDateTime) -> Duration {
+        self.to_timespec() - other.to_timespec()
+    }
+}
+
+impl fmt::String for OffsetDateTime {
+    /// Writes the date and time as in RFC 3339.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::String::fmt(&self.rfc3339(), f)
+    }
+}
+
+/// A wrapper around a date and time and format string that implements Show.
+pub struct DateTimeFmt<'a> {
+    tm: Tm,
+    format: Fmt<'a>,
+}
+
+impl<'a> DateTimeFmt<'a> {
+    fn new(tm: Tm, format: Fmt<'a>) -> Result<DateTimeFmt<'a>, ParseError> {
+        try!(validate_format(TmFmt { tm: &tm, zone: None, format: format }));
+        Ok(DateTimeFmt { tm: tm, format: format })
+    }
+}
+
+impl<'a> fmt::String for DateTimeFmt<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::String::fmt(&TmFmt { tm: &self.tm, zone: None, format: self.format }, f)
+    }
+}
+
+impl<'a> fmt::Show for DateTimeFmt<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::String::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use std::collections::HashSet;
+    use super::{NaiveDateTime, OffsetDateTime};
+    use {Date, Time, Timespec, at_utc, at_offset, strptime};
+    use ParseError::{InvalidSecond, InvalidZoneOffset, InvalidFormatSpecifier};
+
+    fn naive(y: i32, mo: i32, d: i32, h: i32, mi: i32, s: i32) -> NaiveDateTime {
+        NaiveDateTime::new(Date::from_ymd(y, mo, d).unwrap(), Time::from_hms(h, mi, s).unwrap())
+    }
+
+    #[test]
+    fn test_naive_conversions() {
+        let time = Timespec::new(1234567890, 54321);
+        let datetime = NaiveDateTime::from_timespec(time).unwrap();
+        assert_eq!(datetime.date(), Date::from_ymd(2009, 2, 13).unwrap());
+        assert_eq!(datetime.time(), Time::from_hms_nano(23, 31, 30, 54321).unwrap());
+        assert_eq!(datetime.to_timespec(), time);
+        assert_eq!(datetime.to_tm(), at_utc(time));
+        assert_eq!(NaiveDateTime::from_tm(&at_utc(time)), Ok(datetime));
+
+        // `tm_utcoff` is ignored.
+        let local = at_offset(time, -28800);
+        assert_eq!(NaiveDateTime::from_tm(&local).unwrap().to_tm(),
+                   at_utc(time - Duration::hours(8)));
+
+        let mut leap = at_utc(time);
+        leap.tm_sec = 60;
+        assert_eq!(NaiveDateTime::from_tm(&leap), Err(InvalidSecond));
+        assert_eq!(NaiveDateTime::from_timespec(Timespec::new(1 << 60, 0)), None);
+
+        let before = NaiveDateTime::from_timespec(Timespec::new(-1, 0)).unwrap();
+        assert_eq!(before, naive(1969, 12, 31, 23, 59, 59));
+    }
+
+    #[test]
+    fn test_naive_arithmetic() {
+        let datetime = naive(2008, 12, 31, 23, 0, 0);
+        assert_eq!(datetime + Duration::hours(2), naive(2009, 1, 1, 1, 0, 0));
+        assert_eq!(datetime - Duration::days(366), naive(2007, 12, 31, 23, 0, 0));
+        assert_eq!(naive(2009, 1, 1, 1, 0, 0) - datetime, Duration::hours(2));
+        assert!(datetime < naive(2009, 1, 1, 0, 0, 0));
+        assert!(datetime > naive(2008, 12, 31, 22, 59, 59));
+    }
+
+    #[test]
+    fn test_offset_conversions() {
+        let time = Timespec::new(1234567890, 54321);
+        let datetime = OffsetDateTime::from_timespec(time, 32400).unwrap();
+        assert_eq!(datetime.naive(), NaiveDateTime::from_tm(&at_offset(time, 32400)).unwrap());
+        assert_eq!(datetime.utcoff(), 32400);
+        assert_eq!(datetime.to_timespec(), time);
+        assert_eq!(datetime.to_tm(), at_offset(time, 32400));
+        assert_eq!(OffsetDateTime::from_tm(&at_offset(time, 32400)), Ok(datetime));
+
+        let tm = strptime("2009-02-13 18:31:30 -0500", "%Y-%m-%d %H:%M:%S %z").unwrap();
+        let parsed = OffsetDateTime::from_tm(&tm).unwrap();
+        assert_eq!(parsed.to_timespec(), Timespec::new(1234567890, 0));
+        assert_eq!(parsed.to_tm().to_timespec(), Timespec::new(1234567890, 0));
+
+        assert_eq!(OffsetDateTime::new(parsed.naive(), 86_400), Err(InvalidZoneOffset));
+        assert_eq!(OffsetDateTime::from_timespec(time, -86_400), None);
+    }
+
+    #[test]
+    fn test_offset_ord_hash() {
+        let time = Timespec::new(1234567890, 0);
+        let tokyo = OffsetDateTime::from_timespec(time, 32400).unwrap();
+        let new_york = OffsetDateTime::from_timespec(time, -18000).unwrap();
+        let later = OffsetDateTime::from_timespec(time + Duration::seconds(1), -18000).unwrap();
+
+        assert!(tokyo != new_york);
+        assert!(new_york < tokyo);
+        assert!(tokyo < later);
+        assert_eq!(later - tokyo, Duration::seconds(1));
+        assert_eq!(tokyo + Duration::hours(1),
+                   OffsetDateTime::from_timespec(time + Duration::hours(1), 32400).unwrap());
+        assert_eq!((tokyo - Duration::days(1)).utcoff(), 32400);
+
+        let mut set = HashSet::new();
+        set.insert(tokyo);
+        assert!(set.contains(&OffsetDateTime::from_timespec(time, 32400).unwrap()));
+        assert!(!set.contains(&new_york));
+    }
+
+    #[test]
+    fn test_format() {
+        let time = Timespec::new(1234567890, 54321);
+        let utc = NaiveDateTime::from_timespec(time).unwrap();
+        assert_eq!(utc.strftime("%Y-%m-%d %H:%M:%S %z").unwrap().to_string(),
+                   "2009-02-13 23:31:30 -0000".to_string());
+        assert_eq!(utc.to_string(), "2009-02-13T23:31:30.000054321".to_string());
+
+        let local = OffsetDateTime::from_timespec(time, -28800).unwrap();
+        assert_eq!(local.strftime("%a %d %b %T %z").unwrap().to_string(),
+                   "Fri 13 Feb 15:31:30 -0800".to_string());
+        assert_eq!(local.rfc3339().to_string(), "2009-02-13T15:31:30-08:00".to_string());
+        assert_eq!(local.to_string(), "2009-02-13T15:31:30-08:00".to_string());
+        assert_eq!(local.strftime("%Q").unwrap_err(), InvalidFormatSpecifier('Q'));
+    }
+}
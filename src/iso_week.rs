@@ -0,0 +1,135 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! ISO 8601 week dates.
+
+use std::fmt;
+
+use super::{Date, ParseError, Weekday, MAX_YEAR};
+use super::ParseError::{InvalidYear, InvalidWeek};
+use calendar;
+
+/// A week of an ISO 8601 week-numbering year.
+///
+/// ISO weeks run from Monday to Sunday, and week 1 of a year is the week with
+/// the year's first Thursday, so the first and last few days of a calendar
+/// year may belong to a week of the neighbouring ISO year. This is the week
+/// printed by `%G` and `%V`. Weeks are ordered chronologically.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Show)]
+pub struct IsoWeek {
+    year: i32,
+    week: i32,
+}
+
+impl IsoWeek {
+    /// Returns week `week` of the ISO year `year`, or an error if the year
+    /// has no such week.
+    pub fn new(year: i32, week: i32) -> Result<IsoWeek, ParseError> {
+        if week < 1 || week as i64 > calendar::iso_weeks_in_year(year as i64) {
+            return Err(InvalidWeek);
+        }
+        Ok(IsoWeek { year: year, week: week })
+    }
+
+    /// Returns the ISO week that `date` falls in, or `None` for the last
+    /// days of `MAX_YEAR` that belong to week 1 of the following ISO year.
+    pub fn of(date: Date) -> Option<IsoWeek> {
+        let (year, week) = calendar::iso_week(date.year() as i64, date.ordinal() as i64 - 1,
+                                              date.weekday().number() as i64);
+        if year > MAX_YEAR as i64 {
+            return None;
+        }
+        Some(IsoWeek { year: year as i32, week: week as i32 })
+    }
+
+    /// Returns the number of weeks in the ISO year `year`, 52 or 53.
+    pub fn weeks_in_year(year: i32) -> i32 {
+        calendar::iso_weeks_in_year(year as i64) as i32
+    }
+
+    /// Returns the ISO week-numbering year, which differs from the calendar
+    /// year for some days around January 1.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the week number, in `[1, 53]`.
+    pub fn week(&self) -> i32 {
+        self.week
+    }
+
+    /// Returns the date of `weekday` in this week, or an error if it is out
+    /// of the range of `Date`.
+    pub fn day(&self, weekday: Weekday) -> Result<Date, ParseError> {
+        let days = calendar::days_from_iso_week(self.year as i64, self.week as i64,
+                                                weekday.iso_number() as i64);
+        Date::from_days_since_epoch(days).ok_or(InvalidYear)
+    }
+}
+
+impl fmt::String for IsoWeek {
+    /// Writes the week as in ISO 8601, such as "2015-W05".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.year < 0 {
+            write!(f, "-{:04}-W{:02}", -(self.year as i64), self.week)
+        } else {
+            write!(f, "{:04}-W{:02}", self.year, self.week)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IsoWeek;
+    use {Date, ParseError, MAX_YEAR};
+    use Weekday::{Monday, Friday, Sunday};
+
+    #[test]
+    fn test_of() {
+        let date = |y, m, d| Date::from_ymd(y, m, d).unwrap();
+
+        assert_eq!(IsoWeek::of(date(2009, 2, 13)), IsoWeek::new(2009, 7).ok());
+        assert_eq!(IsoWeek::of(date(2008, 12, 29)), IsoWeek::new(2009, 1).ok());
+        assert_eq!(IsoWeek::of(date(2010, 1, 3)), IsoWeek::new(2009, 53).ok());
+        assert_eq!(IsoWeek::of(date(2026, 10, 17)), IsoWeek::new(2026, 42).ok());
+        assert_eq!(date(2026, 10, 17).iso_week().unwrap().year(), 2026);
+        assert_eq!(date(2026, 10, 17).iso_week().unwrap().week(), 42);
+
+        // The last Monday and Tuesday of `MAX_YEAR` are in the next ISO year.
+        assert_eq!(IsoWeek::of(date(MAX_YEAR, 12, 29)).map(|w| w.year()), Some(MAX_YEAR));
+        assert_eq!(IsoWeek::of(date(MAX_YEAR, 12, 30)), None);
+        assert_eq!(date(MAX_YEAR, 12, 31).iso_week(), None);
+    }
+
+    #[test]
+    fn test_day() {
+        let date = |y, m, d| Date::from_ymd(y, m, d).unwrap();
+
+        // 2026-W42-5
+        let week = IsoWeek::new(2026, 42).unwrap();
+        assert_eq!(week.day(Friday), Ok(date(2026, 10, 16)));
+        assert_eq!(week.day(Monday), Ok(date(2026, 10, 12)));
+        assert_eq!(Date::from_iso_week_date(2026, 42, Friday), Ok(date(2026, 10, 16)));
+        assert_eq!(Date::from_iso_week_date(2009, 1, Monday), Ok(date(2008, 12, 29)));
+        assert_eq!(Date::from_iso_week_date(2009, 53, Sunday), Ok(date(2010, 1, 3)));
+        assert_eq!(Date::from_iso_week_date(2010, 53, Sunday), Err(ParseError::InvalidWeek));
+    }
+
+    #[test]
+    fn test_new_fmt() {
+        assert_eq!(IsoWeek::weeks_in_year(2026), 53);
+        assert_eq!(IsoWeek::weeks_in_year(2025), 52);
+        assert!(IsoWeek::new(2026, 53).is_ok());
+        assert_eq!(IsoWeek::new(2025, 53), Err(ParseError::InvalidWeek));
+        assert_eq!(IsoWeek::new(2025, 0), Err(ParseError::InvalidWeek));
+        assert!(IsoWeek::new(2025, 52).unwrap() < IsoWeek::new(2026, 1).unwrap());
+        assert_eq!(IsoWeek::new(2026, 5).unwrap().to_string(), "2026-W05".to_string());
+    }
+}
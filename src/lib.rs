@@ -9,6 +9,23 @@
 // except according to those terms.
 
 //! Simple time handling.
+//!
+//! # Supported range
+//!
+//! A `Timespec` can hold any second that fits in an `i64`, but `Tm` and
+//! `Date` support the years `[MIN_YEAR, MAX_YEAR]`: those that fit in an
+//! `i32` both as they are and, in `tm_year`, counted from 1900. That is a
+//! little over two billion years either side of the epoch.
+//!
+//! Breaking down a `Timespec` whose year is outside this range panics in
+//! `at_utc`, `at_offset`, `at` and the `at` methods of the time zones, and
+//! returns `Err(InvalidYear)` from their `try_` counterparts. Every `Tm`
+//! converts back to a `Timespec`.
+//!
+//! `at` and `now` go through the C library, which on targets with a 32-bit
+//! `time_t` only covers 1901-12-13 to 2038-01-19. Outside of what it covers,
+//! they use the rules of the local time zone, read once on first use, so
+//! they have no such limit.
 
 #![doc(html_logo_url = "http://www.rust-lang.org/logos/rust-logo-128x128-blk-v2.png",
        html_favicon_url = "http://www.rust-lang.org/favicon.ico",
@@ -23,29 +40,65 @@ extern crate "rustc-serialize" as rustc_serialize;
 
 use std::cmp::Ordering;
 use std::fmt;
+use std::i32;
 use std::io::BufReader;
-use std::num::SignedInt;
+use std::num::{Float, SignedInt};
 use std::ops::{Add, Sub};
+use std::ptr;
 use std::time::Duration;
 
 use self::Fmt::{FmtCtime, FmtRfc3339, FmtStr};
 use self::ParseError::{InvalidDay, InvalidDayOfMonth, InvalidDayOfWeek,
                        InvalidDayOfYear, InvalidFormatSpecifier, InvalidHour,
-                       InvalidMinute, InvalidMonth, InvalidSecond, InvalidTime,
-                       InvalidYear, InvalidZoneOffset, MissingFormatConverter,
-                       UnexpectedCharacter};
+                       InvalidMinute, InvalidMonth, InvalidNanosecond, InvalidSecond,
+                       InvalidTime, InvalidWeek, InvalidYear, InvalidZoneOffset,
+                       MissingFormatConverter, UnexpectedCharacter};
+
+pub use date::{Date, DayOverflow, Period, is_leap_year, days_in_year, days_in_month};
+pub use datetime::{NaiveDateTime, OffsetDateTime, DateTimeFmt};
+pub use iso_week::IsoWeek;
+pub use local::LocalZone;
+pub use month::{Month, MONTHS};
+pub use posix_tz::PosixTz;
+pub use round::TimeUnit;
+pub use time_of_day::{Time, TimeFmt};
+pub use weekday::{Weekday, WEEKDAYS};
+pub use zoneinfo::{TimeZone, LocalTimeType, Transition, Transitions, ZoneError,
+                   LocalResult, Disambiguate};
+
+mod calendar;
+mod date;
+mod datetime;
+mod iso_week;
+mod local;
+mod month;
+mod posix_tz;
+mod round;
+mod time_of_day;
+mod weekday;
+mod zoneinfo;
 
 static NSEC_PER_SEC: i32 = 1_000_000_000;
 
+/// The earliest year supported by `Tm` and `Date`, -2147481748.
+pub static MIN_YEAR: i32 = i32::MIN + 1900;
+
+/// The latest year supported by `Tm` and `Date`, 2147483647.
+pub static MAX_YEAR: i32 = i32::MAX;
+
 mod rustrt {
+    use libc::{c_char, size_t};
     use super::Tm;
 
     extern {
         pub fn rust_time_tzset();
-        pub fn rust_time_gmtime(sec: i64, nsec: i32, result: &mut Tm);
-        pub fn rust_time_localtime(sec: i64, nsec: i32, result: &mut Tm);
+        pub fn rust_time_localtime(sec: i64, nsec: i32, result: &mut Tm,
+                                   abbr: *mut c_char, len: size_t) -> i32;
+
+        // The UTC conversions are done in Rust; these are only used to check
+        // them against the C library.
+        pub fn rust_time_gmtime(sec: i64, nsec: i32, result: &mut Tm) -> i32;
         pub fn rust_time_timegm(tm: &Tm) -> i64;
-        pub fn rust_time_mktime(tm: &Tm) -> i64;
     }
 }
 
@@ -92,6 +145,106 @@ impl Timespec {
         assert!(nsec >= 0 && nsec < NSEC_PER_SEC);
         Timespec { sec: sec, nsec: nsec }
     }
+
+    /// Returns the Modified Julian Date of this instant as whole days since
+    /// 1858-11-17 00:00 UTC and nanoseconds into the day. Unlike the
+    /// fractional day counts, this is exact.
+    pub fn modified_julian_day_and_nanos(&self) -> (i64, i64) {
+        let day = calendar::div_floor(self.sec, calendar::SECS_PER_DAY);
+        let nanos = (self.sec - day * calendar::SECS_PER_DAY) * NSEC_PER_SEC as i64 +
+                    self.nsec as i64;
+        (day + calendar::UNIX_EPOCH_MJD, nanos)
+    }
+
+    /// Returns the instant `nanos` nanoseconds after the start of the
+    /// Modified Julian Day `day`. `nanos` may be outside a single day.
+    pub fn from_modified_julian_day_and_nanos(day: i64, nanos: i64) -> Timespec {
+        let nsec_per_day = calendar::SECS_PER_DAY * NSEC_PER_SEC as i64;
+        let day = day - calendar::UNIX_EPOCH_MJD + calendar::div_floor(nanos, nsec_per_day);
+        let nanos = calendar::mod_floor(nanos, nsec_per_day);
+        Timespec::new(day * calendar::SECS_PER_DAY + nanos / NSEC_PER_SEC as i64,
+                      (nanos % NSEC_PER_SEC as i64) as i32)
+    }
+
+    /// Returns the Julian Date, the number of days since noon UTC on
+    /// January 1, 4713 BC in the proleptic Julian calendar.
+    ///
+    /// An `f64` holds Julian Dates of the present era to within about 40
+    /// microseconds.
+    pub fn julian_day(&self) -> f64 {
+        let (day, fraction) = self.day_fraction();
+        (day + calendar::MJD_TO_JDN - 1) as f64 + (fraction + 0.5)
+    }
+
+    /// Returns the instant at the Julian Date `jd`, to the nearest
+    /// nanosecond.
+    ///
+    /// Panics if `jd` is not finite or is too far from the present to be
+    /// held by a `Timespec`.
+    pub fn from_julian_day(jd: f64) -> Timespec {
+        let day = jd.floor();
+        Timespec::from_day_fraction(day - (calendar::MJD_TO_JDN - 1) as f64,
+                                    (jd - day) - 0.5)
+    }
+
+    /// Returns the Modified Julian Date, the number of days since midnight
+    /// UTC on 1858-11-17. It is the Julian Date minus 2400000.5.
+    pub fn modified_julian_date(&self) -> f64 {
+        let (day, fraction) = self.day_fraction();
+        day as f64 + fraction
+    }
+
+    /// Returns the instant at the Modified Julian Date `mjd`, to the nearest
+    /// nanosecond.
+    ///
+    /// Panics if `mjd` is not finite or is too far from the present to be
+    /// held by a `Timespec`.
+    pub fn from_modified_julian_date(mjd: f64) -> Timespec {
+        let day = mjd.floor();
+        Timespec::from_day_fraction(day, mjd - day)
+    }
+
+    /// Returns the Rata Die, the number of days since midnight UTC before
+    /// 0001-01-01, so that all of 0001-01-01 is in `[1, 2)`.
+    pub fn rata_die(&self) -> f64 {
+        let (day, fraction) = self.day_fraction();
+        (day + calendar::MJD_TO_RATA_DIE) as f64 + fraction
+    }
+
+    /// Returns the instant at the Rata Die `rd`, to the nearest nanosecond.
+    ///
+    /// Panics if `rd` is not finite or is too far from the present to be
+    /// held by a `Timespec`.
+    pub fn from_rata_die(rd: f64) -> Timespec {
+        let day = rd.floor();
+        Timespec::from_day_fraction(day - calendar::MJD_TO_RATA_DIE as f64, rd - day)
+    }
+
+    /// Returns the start of the `unit` of wall-clock time in `zone` that
+    /// contains this instant, such as the local midnight that starts its day.
+    pub fn trunc_to(&self, unit: TimeUnit, zone: &TimeZone) -> Timespec {
+        round::trunc(*self, unit, zone)
+    }
+
+    /// Returns the boundary between units of wall-clock time in `zone`
+    /// nearest to this instant, rounding halfway cases up.
+    pub fn round_to(&self, unit: TimeUnit, zone: &TimeZone) -> Timespec {
+        round::round(*self, unit, zone)
+    }
+
+    /// Splits the instant into a Modified Julian Day and the fraction of the
+    /// day, so that large day counts do not cost the fraction its precision.
+    fn day_fraction(&self) -> (i64, f64) {
+        let (day, nanos) = self.modified_julian_day_and_nanos();
+        (day, nanos as f64 / (calendar::SECS_PER_DAY * NSEC_PER_SEC as i64) as f64)
+    }
+
+    fn from_day_fraction(day: f64, fraction: f64) -> Timespec {
+        // Beyond this, the day's seconds no longer fit in an `i64`.
+        assert!(day.abs() < 1e14, "day count out of range");
+        let nanos = (fraction * (calendar::SECS_PER_DAY * NSEC_PER_SEC as i64) as f64).round();
+        Timespec::from_modified_julian_day_and_nanos(day as i64, nanos as i64)
+    }
 }
 
 impl Add<Duration> for Timespec {
@@ -352,22 +505,18 @@ pub struct Tm {
 impl Add<Duration> for Tm {
     type Output = Tm;
 
-    /// The resulting Tm is in UTC.
-    // FIXME:  The resulting Tm should have the same timezone as `self`; however, we need a
-    // function such as `at_tm(clock: Timespec, offset: i32)` for this.
+    /// The resulting Tm has the same UTC offset as `self`.
     fn add(self, other: Duration) -> Tm {
-        at_utc(self.to_timespec() + other)
+        at_offset(self.to_timespec() + other, self.tm_utcoff)
     }
 }
 
 impl Sub<Duration> for Tm {
     type Output = Tm;
 
-    /// The resulting Tm is in UTC.
-    // FIXME:  The resulting Tm should have the same timezone as `self`; however, we need a
-    // function such as `at_tm(clock: Timespec, offset: i32)` for this.
+    /// The resulting Tm has the same UTC offset as `self`.
     fn sub(self, other: Duration) -> Tm {
-        at_utc(self.to_timespec() - other)
+        at_offset(self.to_timespec() - other, self.tm_utcoff)
     }
 }
 
@@ -400,13 +549,16 @@ pub fn empty_tm() -> Tm {
 }
 
 /// Returns the specified time in UTC
+///
+/// Panics if the year is outside `[MIN_YEAR, MAX_YEAR]`.
 pub fn at_utc(clock: Timespec) -> Tm {
-    unsafe {
-        let Timespec { sec, nsec } = clock;
-        let mut tm = empty_tm();
-        rustrt::rust_time_gmtime(sec, nsec, &mut tm);
-        tm
-    }
+    at_offset(clock, 0)
+}
+
+/// Returns the specified time in UTC, or `Err(InvalidYear)` if the year is
+/// outside `[MIN_YEAR, MAX_YEAR]`.
+pub fn try_at_utc(clock: Timespec) -> Result<Tm, ParseError> {
+    try_at_offset(clock, 0)
 }
 
 /// Returns the current time in UTC
@@ -414,13 +566,68 @@ pub fn now_utc() -> Tm {
     at_utc(get_time())
 }
 
+/// Returns the specified time at a fixed offset of `utcoff` seconds east of
+/// UTC. The `tm_isdst` field of the result is 0.
+///
+/// Panics if the year is outside `[MIN_YEAR, MAX_YEAR]`.
+pub fn at_offset(clock: Timespec, utcoff: i32) -> Tm {
+    calendar::tm_from_seconds(clock.sec, clock.nsec, utcoff)
+}
+
+/// Returns the specified time at a fixed offset of `utcoff` seconds east of
+/// UTC, or `Err(InvalidYear)` if the year is outside `[MIN_YEAR, MAX_YEAR]`.
+pub fn try_at_offset(clock: Timespec, utcoff: i32) -> Result<Tm, ParseError> {
+    calendar::checked_tm_from_seconds(clock.sec, clock.nsec, utcoff).ok_or(InvalidYear)
+}
+
 /// Returns the specified time in the local timezone
+///
+/// Panics where `try_at` returns an error.
 pub fn at(clock: Timespec) -> Tm {
-    unsafe {
-        let Timespec { sec, nsec } = clock;
-        let mut tm = empty_tm();
-        rustrt::rust_time_localtime(sec, nsec, &mut tm);
-        tm
+    try_at(clock).ok().expect("time out of the range of the local time zone")
+}
+
+/// Returns the specified time in the local timezone, or `Err(InvalidYear)`
+/// if the year is outside `[MIN_YEAR, MAX_YEAR]`.
+///
+/// Times the C library cannot represent, such as those after 2038 where
+/// `time_t` has 32 bits, are converted with the rules of the local time
+/// zone, read once on first use. If that cannot be loaded either, they give
+/// `Err(InvalidYear)` too.
+pub fn try_at(clock: Timespec) -> Result<Tm, ParseError> {
+    let mut tm = empty_tm();
+    let ok = unsafe {
+        rustrt::rust_time_localtime(clock.sec, clock.nsec, &mut tm, ptr::null_mut(), 0) != 0
+    };
+    if ok && tm.tm_year <= MAX_YEAR - 1900 {
+        Ok(tm)
+    } else {
+        local::fallback().map_or(Err(InvalidYear), |zone| zone.try_at(clock))
+    }
+}
+
+/// Returns the specified time in the local timezone, together with the zone
+/// abbreviation, such as "PST", so that `%Z` and `rfc822` can print it.
+///
+/// The abbreviation is the one the C library's `strftime` prints for `%Z`,
+/// which on Windows is the full name of the zone. Panics where `try_at`
+/// returns an error.
+pub fn at_zoned(clock: Timespec) -> ZonedTm {
+    let mut tm = empty_tm();
+    let mut abbr = [0u8; 64];
+    let ok = unsafe {
+        rustrt::rust_time_localtime(clock.sec, clock.nsec, &mut tm,
+                                    abbr.as_mut_ptr() as *mut libc::c_char,
+                                    abbr.len() as libc::size_t) != 0
+    };
+    if ok && tm.tm_year <= MAX_YEAR - 1900 {
+        let len = abbr.iter().position(|&b| b == 0).unwrap_or(0);
+        ZonedTm { tm: tm, abbr: String::from_utf8_lossy(abbr.slice_to(len)).into_owned() }
+    } else {
+        match local::fallback() {
+            Some(zone) => zone.at_zoned(clock),
+            None => panic!("time out of the range of the local time zone"),
+        }
     }
 }
 
@@ -429,17 +636,262 @@ pub fn now() -> Tm {
     at(get_time())
 }
 
+/// Returns the current time in the local timezone, together with the zone
+/// abbreviation. See `at_zoned`.
+pub fn now_zoned() -> ZonedTm {
+    at_zoned(get_time())
+}
+
+/// Returns the specified time in the given time zone
+pub fn at_in(clock: Timespec, zone: &TimeZone) -> Tm {
+    zone.at(clock)
+}
+
+/// Returns the current time in the given time zone
+pub fn now_in(zone: &TimeZone) -> Tm {
+    at_in(get_time(), zone)
+}
+
 impl Tm {
     /// Convert time to the seconds from January 1, 1970
+    ///
+    /// The fields are read as a wall-clock time `tm_utcoff` seconds east of
+    /// UTC, whatever the local timezone of the process. `tm_wday`, `tm_yday`
+    /// and `tm_isdst` are ignored, and fields outside their usual ranges are
+    /// carried into the next larger unit.
     pub fn to_timespec(&self) -> Timespec {
-        unsafe {
-            let sec = match self.tm_utcoff {
-                0 => rustrt::rust_time_timegm(self),
-                _     => rustrt::rust_time_mktime(self)
-            };
+        let sec = calendar::local_seconds(self) - self.tm_utcoff as i64;
+        Timespec::new(sec, self.tm_nsec)
+    }
 
-            Timespec::new(sec, self.tm_nsec)
+    /// Returns the Julian Date of the instant this time names. See
+    /// `Timespec::julian_day`.
+    pub fn julian_day(&self) -> f64 {
+        self.to_timespec().julian_day()
+    }
+
+    /// Returns the Modified Julian Date of the instant this time names. See
+    /// `Timespec::modified_julian_date`.
+    pub fn modified_julian_date(&self) -> f64 {
+        self.to_timespec().modified_julian_date()
+    }
+
+    /// Returns the Rata Die of the instant this time names. See
+    /// `Timespec::rata_die`.
+    pub fn rata_die(&self) -> f64 {
+        self.to_timespec().rata_die()
+    }
+
+    /// Carries fields outside their usual ranges into the next larger unit,
+    /// like `mktime` does, and recomputes `tm_wday` and `tm_yday`.
+    ///
+    /// For example, January 45 becomes February 14, and a `tm_sec` of 60
+    /// moves to the next minute. `tm_nsec` is carried into `tm_sec` too.
+    /// `tm_utcoff` and `tm_isdst` are kept, and the local time zone is not
+    /// consulted. Panics if the year ends up outside `[MIN_YEAR, MAX_YEAR]`.
+    pub fn normalize(&mut self) {
+        let nsec = self.tm_nsec as i64;
+        let sec = calendar::local_seconds(self) +
+                  calendar::div_floor(nsec, NSEC_PER_SEC as i64);
+        let nsec = calendar::mod_floor(nsec, NSEC_PER_SEC as i64) as i32;
+        *self = Tm {
+            tm_isdst: self.tm_isdst,
+            tm_utcoff: self.tm_utcoff,
+            ..calendar::tm_from_seconds(sec, nsec, 0)
+        };
+    }
+
+    /// Returns the quarter of the year, in `[1, 4]`, with January to March
+    /// being the first. This is what `%q` prints.
+    pub fn quarter(&self) -> i32 {
+        self.tm_mon / 3 + 1
+    }
+
+    /// Returns the fiscal year of the local date when fiscal years start on
+    /// the first day of `start`, numbered by the calendar year they start in.
+    /// See `Date::fiscal_year`.
+    pub fn fiscal_year(&self, start: Month) -> i32 {
+        let (year, _) = calendar::fiscal_month(self.tm_year as i64 + 1900,
+                                               self.tm_mon as i64 + 1, start.number() as i64);
+        year as i32
+    }
+
+    /// Returns the quarter, in `[1, 4]`, of the fiscal year starting in
+    /// `start` that the local date falls in.
+    pub fn fiscal_quarter(&self, start: Month) -> i32 {
+        let (_, months) = calendar::fiscal_month(self.tm_year as i64 + 1900,
+                                                 self.tm_mon as i64 + 1, start.number() as i64);
+        months as i32 / 3 + 1
+    }
+
+    /// Returns the number of calendar days from the local date of `self` to
+    /// the local date of `end`, negative if `end` is earlier.
+    ///
+    /// Only the dates count: the times of day and `tm_utcoff` are ignored, so
+    /// days made longer or shorter by daylight saving time count as one day.
+    pub fn days_until(&self, end: &Tm) -> i64 {
+        calendar::div_floor(calendar::local_seconds(end), calendar::SECS_PER_DAY) -
+            calendar::div_floor(calendar::local_seconds(self), calendar::SECS_PER_DAY)
+    }
+
+    /// Returns the calendar difference from the local date of `self` to the
+    /// local date of `end`, in years, months and days. The times of day and
+    /// `tm_utcoff` are ignored.
+    pub fn period_until(&self, end: &Tm) -> Period {
+        let (mut from, mut to) = (*self, *end);
+        from.normalize();
+        to.normalize();
+        date::period_between((from.tm_year as i64 + 1900, from.tm_mon as i64 + 1,
+                              from.tm_mday as i64),
+                             (to.tm_year as i64 + 1900, to.tm_mon as i64 + 1,
+                              to.tm_mday as i64))
+    }
+
+    /// Adds `months` calendar months, going back for negative `months`, and
+    /// resolves days past the end of the target month according to `policy`.
+    ///
+    /// The time of day, `tm_utcoff` and `tm_isdst` are kept, so in a zone with
+    /// daylight saving time the result may need to be read again with
+    /// `to_timespec_in`. `tm_wday` and `tm_yday` are recomputed. Fields
+    /// outside their usual ranges are carried first, as by `normalize`, so
+    /// January 40 moves from February 9.
+    ///
+    /// Panics if the year, before or after the shift, is outside
+    /// `[MIN_YEAR, MAX_YEAR]`.
+    pub fn add_months(&self, months: i32, policy: DayOverflow) -> Tm {
+        self.shift_months(months as i64, policy)
+    }
+
+    /// Subtracts `months` calendar months, like `add_months`.
+    pub fn sub_months(&self, months: i32, policy: DayOverflow) -> Tm {
+        self.shift_months(-(months as i64), policy)
+    }
+
+    /// Adds `years` years, going back for negative `years`, like
+    /// `add_months`. `policy` decides whether February 29 becomes February 28
+    /// or March 1 in common years.
+    ///
+    /// Panics if the year, before or after the shift, is outside
+    /// `[MIN_YEAR, MAX_YEAR]`.
+    pub fn add_years(&self, years: i32, policy: DayOverflow) -> Tm {
+        self.shift_months(years as i64 * 12, policy)
+    }
+
+    /// Subtracts `years` years, like `add_years`.
+    pub fn sub_years(&self, years: i32, policy: DayOverflow) -> Tm {
+        self.shift_months(-(years as i64) * 12, policy)
+    }
+
+    fn shift_months(&self, months: i64, policy: DayOverflow) -> Tm {
+        let mut from = *self;
+        from.normalize();
+        let (year, month, day) = date::shift_months(from.tm_year as i64 + 1900,
+                                                    from.tm_mon as i64 + 1,
+                                                    from.tm_mday as i64, months, policy);
+        let mut tm = Tm {
+            tm_year: (year - 1900) as i32,
+            tm_mon: (month - 1) as i32,
+            tm_mday: day as i32,
+            ..from
+        };
+        tm.normalize();
+        tm
+    }
+
+    /// Checks that every field is within its range and that `tm_wday` and
+    /// `tm_yday` agree with the date, reporting the first offending field.
+    ///
+    /// `tm_sec` may be 60 for a leap second, `tm_mday` must exist in its
+    /// month, and `tm_utcoff` must be less than a day. Any `tm_year` and
+    /// `tm_isdst` is accepted.
+    pub fn validate(&self) -> Result<(), ParseError> {
+        if self.tm_sec < 0 || self.tm_sec > 60 {
+            return Err(InvalidSecond);
+        }
+        if self.tm_min < 0 || self.tm_min > 59 {
+            return Err(InvalidMinute);
+        }
+        if self.tm_hour < 0 || self.tm_hour > 23 {
+            return Err(InvalidHour);
+        }
+        if self.tm_mon < 0 || self.tm_mon > 11 {
+            return Err(InvalidMonth);
         }
+        let year = self.tm_year as i64 + 1900;
+        let month = self.tm_mon as i64 + 1;
+        if self.tm_mday < 1 || self.tm_mday as i64 > calendar::days_in_month(year, month) {
+            return Err(InvalidDayOfMonth);
+        }
+        let days = calendar::days_from_civil(year, month, self.tm_mday as i64);
+        if self.tm_wday as i64 != calendar::weekday_from_days(days) {
+            return Err(InvalidDayOfWeek);
+        }
+        if self.tm_yday as i64 != days - calendar::days_from_civil(year, 1, 1) {
+            return Err(InvalidDayOfYear);
+        }
+        if self.tm_nsec < 0 || self.tm_nsec >= NSEC_PER_SEC {
+            return Err(InvalidNanosecond);
+        }
+        if self.tm_utcoff <= -86_400 || self.tm_utcoff >= 86_400 {
+            return Err(InvalidZoneOffset);
+        }
+        Ok(())
+    }
+
+    /// Returns the start of the `unit` of wall-clock time in `zone` that
+    /// contains the instant this time names, as a time in `zone`.
+    ///
+    /// Only the instant of `self` is used. The units follow the wall clock of
+    /// `zone`, and `tm_utcoff` and `tm_isdst` of the result are those of
+    /// `zone` at the start, not those of `self`: truncating a time at
+    /// `+05:30` to the day in UTC gives midnight UTC.
+    pub fn trunc_to(&self, unit: TimeUnit, zone: &TimeZone) -> Tm {
+        zone.at(self.to_timespec().trunc_to(unit, zone))
+    }
+
+    /// Returns the boundary between units of wall-clock time in `zone`
+    /// nearest to the instant this time names, as a time in `zone`.
+    /// `tm_utcoff` and `tm_isdst` are those of `zone` at the boundary, as in
+    /// `trunc_to`.
+    pub fn round_to(&self, unit: TimeUnit, zone: &TimeZone) -> Tm {
+        zone.at(self.to_timespec().round_to(unit, zone))
+    }
+
+    /// Returns the first instant of the `unit` of wall-clock time in `zone`
+    /// containing the instant this time names, such as midnight at the start
+    /// of its day, as a time in `zone`.
+    ///
+    /// `tm_utcoff` and `tm_isdst` are those in effect at the start, which may
+    /// differ from those of `self`: the month of a PST time in November
+    /// starts at 00:00 PDT. On a day that daylight saving time makes start at
+    /// 01:00, this is 01:00. Unlike `trunc_to`, which counts every instant
+    /// the clock shows a boundary, this is the first instant of the unit as
+    /// a period of the calendar, so a day whose midnight the clock shows
+    /// twice starts at the first. For a time with a fixed offset, such as
+    /// one from `at_offset` or `strptime`, pass
+    /// `TimeZone::fixed(tm.tm_utcoff)` to keep that offset.
+    pub fn start_of(&self, unit: TimeUnit, zone: &TimeZone) -> Tm {
+        let (start, _) = round::period(self.to_timespec(), unit, zone);
+        zone.at(start)
+    }
+
+    /// Returns the last nanosecond of the `unit` of wall-clock time in `zone`
+    /// containing the instant this time names, such as 23:59:59.999999999 at
+    /// the end of its day, as a time in `zone`. `tm_utcoff` and `tm_isdst`
+    /// are those in effect then, as in `start_of`.
+    pub fn end_of(&self, unit: TimeUnit, zone: &TimeZone) -> Tm {
+        let (_, next) = round::period(self.to_timespec(), unit, zone);
+        zone.at(next - Duration::nanoseconds(1))
+    }
+
+    /// Convert the wall-clock time in `self` to the seconds from January 1,
+    /// 1970, reading it in the given time zone. `tm_utcoff` is ignored.
+    ///
+    /// The result is `LocalResult::None` for times skipped by a transition,
+    /// such as the start of daylight saving time, and
+    /// `LocalResult::Ambiguous` for times that occur twice.
+    pub fn to_timespec_in(&self, zone: &TimeZone) -> LocalResult<Timespec> {
+        zone.local_to_timespec(self)
     }
 
     /// Convert time to the local timezone
@@ -447,6 +899,12 @@ impl Tm {
         at(self.to_timespec())
     }
 
+    /// Convert time to the local timezone, keeping the zone abbreviation for
+    /// `%Z` and `rfc822`. See `at_zoned`.
+    pub fn to_local_zoned(&self) -> ZonedTm {
+        at_zoned(self.to_timespec())
+    }
+
     /// Convert time to the UTC
     pub fn to_utc(&self) -> Tm {
         at_utc(self.to_timespec())
@@ -461,6 +919,7 @@ impl Tm {
     pub fn ctime(&self) -> TmFmt {
         TmFmt {
             tm: self,
+            zone: None,
             format: FmtCtime,
         }
     }
@@ -474,14 +933,19 @@ impl Tm {
     pub fn asctime(&self) -> TmFmt {
         TmFmt {
             tm: self,
+            zone: None,
             format: FmtStr("%c"),
         }
     }
 
-    /// Formats the time according to the format string.
+    /// Formats the time according to the format string. Fails with the error
+    /// from `validate` if a field the format prints is invalid. Conversions
+    /// computed from the whole date, such as `%F` and `%s`, need every field
+    /// to be valid.
     pub fn strftime<'a>(&'a self, format: &'a str) -> Result<TmFmt<'a>, ParseError> {
         validate_format(TmFmt {
             tm: self,
+            zone: None,
             format: FmtStr(format),
         })
     }
@@ -489,7 +953,13 @@ impl Tm {
     /**
      * Returns a TmFmt that outputs according to RFC 822.
      *
-     * local: "Thu, 22 Mar 2012 07:53:18 PST"
+     * A `Tm` does not know the abbreviation of its time zone, so for times
+     * that are not in UTC the zone, printed by `%Z`, is empty. Use
+     * `ZonedTm::rfc822`, with a `ZonedTm` from `at_zoned` or
+     * `Tm::to_local_zoned`, to write the abbreviation, or `rfc822z` to write
+     * the numeric offset.
+     *
+     * local: "Thu, 22 Mar 2012 07:53:18 "
      * utc:   "Thu, 22 Mar 2012 14:53:18 GMT"
      */
     pub fn rfc822(&self) -> TmFmt {
@@ -500,6 +970,7 @@ impl Tm {
         };
         TmFmt {
             tm: self,
+            zone: None,
             format: FmtStr(fmt),
         }
     }
@@ -513,6 +984,7 @@ impl Tm {
     pub fn rfc822z(&self) -> TmFmt {
         TmFmt {
             tm: self,
+            zone: None,
             format: FmtStr("%a, %d %b %Y %T %z"),
         }
     }
@@ -527,11 +999,58 @@ impl Tm {
     pub fn rfc3339<'a>(&'a self) -> TmFmt {
         TmFmt {
             tm: self,
+            zone: None,
             format: FmtRfc3339,
         }
     }
 }
 
+/// A broken-down time together with the abbreviation of the time zone it was
+/// computed in, such as "PST" or "CEST".
+///
+/// `at_zoned`, `now_zoned`, `Tm::to_local_zoned`, `TimeZone::at_zoned` and
+/// `LocalZone::at_zoned` return this so that `%Z` and `rfc822` can print the
+/// zone by name. A plain `Tm` prints an empty `%Z` unless it is in UTC.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct ZonedTm {
+    /// The broken-down time.
+    pub tm: Tm,
+
+    /// The abbreviation of the local time type in effect at `tm`.
+    pub abbr: String,
+}
+
+impl ZonedTm {
+    /// Formats the time according to the format string. `%Z` is replaced by
+    /// the zone abbreviation.
+    pub fn strftime<'a>(&'a self, format: &'a str) -> Result<TmFmt<'a>, ParseError> {
+        validate_format(TmFmt {
+            tm: &self.tm,
+            zone: Some(self.abbr.as_slice()),
+            format: FmtStr(format),
+        })
+    }
+
+    /**
+     * Returns a TmFmt that outputs according to RFC 822.
+     *
+     * local: "Thu, 22 Mar 2012 07:53:18 PST"
+     * utc:   "Thu, 22 Mar 2012 14:53:18 GMT"
+     */
+    pub fn rfc822(&self) -> TmFmt {
+        let fmt = if self.tm.tm_utcoff == 0 {
+            "%a, %d %b %Y %T GMT"
+        } else {
+            "%a, %d %b %Y %T %Z"
+        };
+        TmFmt {
+            tm: &self.tm,
+            zone: Some(self.abbr.as_slice()),
+            format: FmtStr(fmt),
+        }
+    }
+}
+
 #[derive(Copy, PartialEq, Show)]
 pub enum ParseError {
     InvalidSecond,
@@ -545,6 +1064,8 @@ pub enum ParseError {
     InvalidDayOfYear,
     InvalidZoneOffset,
     InvalidTime,
+    InvalidNanosecond,
+    InvalidWeek,
     MissingFormatConverter,
     InvalidFormatSpecifier(char),
     UnexpectedCharacter(char, char),
@@ -564,6 +1085,8 @@ impl fmt::String for ParseError {
             InvalidDayOfYear => write!(f, "Invalid day of the year."),
             InvalidZoneOffset => write!(f, "Invalid zone offset."),
             InvalidTime => write!(f, "Invalid time."),
+            InvalidNanosecond => write!(f, "Invalid nanosecond."),
+            InvalidWeek => write!(f, "Invalid week."),
             MissingFormatConverter => write!(f, "Missing format converter after `%`"),
             InvalidFormatSpecifier(ch) => write!(f, "Invalid format specifier: %{}", ch),
             UnexpectedCharacter(a, b) => write!(f, "Expected: {}, found: {}.", a, b),
@@ -572,11 +1095,18 @@ impl fmt::String for ParseError {
 }
 
 /// A wrapper around a `Tm` and format string that implements Show.
+///
+/// `%q` prints the quarter of the calendar year. There is no specifier for
+/// the fiscal year or quarter, as those depend on the month the fiscal year
+/// starts in, which a format string cannot give; use `Tm::fiscal_year` and
+/// `Tm::fiscal_quarter`.
 pub struct TmFmt<'a> {
     tm: &'a Tm,
+    zone: Option<&'a str>,
     format: Fmt<'a>
 }
 
+#[derive(Copy, Clone)]
 enum Fmt<'a> {
     FmtStr(&'a str),
     FmtRfc3339,
@@ -584,13 +1114,6 @@ enum Fmt<'a> {
 }
 
 fn validate_format<'a>(fmt: TmFmt<'a>) -> Result<TmFmt<'a>, ParseError> {
-
-    match (fmt.tm.tm_wday, fmt.tm.tm_mon) {
-        (0...6, 0...11) => (),
-        (_wday, 0...11) => return Err(InvalidDayOfWeek),
-        (0...6, _mon) => return Err(InvalidMonth),
-        _ => return Err(InvalidDay)
-    }
     match fmt.format {
         FmtStr(ref s) => {
             let mut chars = s.chars();
@@ -598,52 +1121,7 @@ fn validate_format<'a>(fmt: TmFmt<'a>) -> Result<TmFmt<'a>, ParseError> {
                 match chars.next() {
                     Some('%') => {
                         match chars.next() {
-                            Some('A') |
-                            Some('a') |
-                            Some('B') |
-                            Some('b') |
-                            Some('C') |
-                            Some('c') |
-                            Some('D') |
-                            Some('d') |
-                            Some('e') |
-                            Some('F') |
-                            Some('f') |
-                            Some('G') |
-                            Some('g') |
-                            Some('H') |
-                            Some('h') |
-                            Some('I') |
-                            Some('j') |
-                            Some('k') |
-                            Some('l') |
-                            Some('M') |
-                            Some('m') |
-                            Some('n') |
-                            Some('P') |
-                            Some('p') |
-                            Some('R') |
-                            Some('r') |
-                            Some('S') |
-                            Some('s') |
-                            Some('T') |
-                            Some('t') |
-                            Some('U') |
-                            Some('u') |
-                            Some('V') |
-                            Some('v') |
-                            Some('W') |
-                            Some('w') |
-                            Some('X') |
-                            Some('x') |
-                            Some('Y') |
-                            Some('y') |
-                            Some('Z') |
-                            Some('z') |
-                            Some('+') |
-                            Some('%')
-                                => (),
-                            Some(c) => return Err(InvalidFormatSpecifier(c)),
+                            Some(c) => try!(validate_specifier(fmt.tm, c)),
                             None => return Err(MissingFormatConverter),
                         }
                     },
@@ -657,58 +1135,47 @@ fn validate_format<'a>(fmt: TmFmt<'a>) -> Result<TmFmt<'a>, ParseError> {
     Ok(fmt)
 }
 
+/// Checks the fields of `tm` that the conversion `%ch` prints, as
+/// `Tm::validate` does. Conversions of a single field only need that field to
+/// be in range, so that the result of `strptime` with a partial format can be
+/// formatted again; those computed from the whole date need all of `tm` to be
+/// valid.
+fn validate_specifier(tm: &Tm, ch: char) -> Result<(), ParseError> {
+    fn check(ok: bool, err: ParseError) -> Result<(), ParseError> {
+        if ok { Ok(()) } else { Err(err) }
+    }
+    let hour = check(tm.tm_hour >= 0 && tm.tm_hour <= 23, InvalidHour);
+    let minute = check(tm.tm_min >= 0 && tm.tm_min <= 59, InvalidMinute);
+    let second = check(tm.tm_sec >= 0 && tm.tm_sec <= 60, InvalidSecond);
+
+    match ch {
+        'A' | 'a' | 'u' | 'w' => check(tm.tm_wday >= 0 && tm.tm_wday <= 6, InvalidDayOfWeek),
+        'B' | 'b' | 'h' | 'm' | 'q' => check(tm.tm_mon >= 0 && tm.tm_mon <= 11, InvalidMonth),
+        'd' | 'e' => check(tm.tm_mday >= 1 && tm.tm_mday <= 31, InvalidDayOfMonth),
+        'j' => check(tm.tm_yday >= 0 && tm.tm_yday <= 365, InvalidDayOfYear),
+        'H' | 'I' | 'k' | 'l' | 'P' | 'p' => hour,
+        'M' => minute,
+        'S' => second,
+        'R' => hour.and(minute),
+        'r' | 'T' | 'X' => hour.and(minute).and(second),
+        'f' => check(tm.tm_nsec >= 0 && tm.tm_nsec < NSEC_PER_SEC, InvalidNanosecond),
+        'Z' | 'z' => check(tm.tm_utcoff > -86_400 && tm.tm_utcoff < 86_400, InvalidZoneOffset),
+        'C' | 'Y' | 'y' | 'n' | 't' | '%' => Ok(()),
+        'c' | 'D' | 'F' | 'G' | 'g' | 's' | 'U' | 'V' | 'v' | 'W' | 'x' | '+' => tm.validate(),
+        c => Err(InvalidFormatSpecifier(c)),
+    }
+}
+
 impl<'a> fmt::String for TmFmt<'a> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fn is_leap_year(year: i32) -> bool {
-            (year % 4 == 0) && ((year % 100 != 0) || (year % 400 == 0))
-        }
-
-        fn days_in_year(year: i32) -> i32 {
-            if is_leap_year(year) { 366 }
-            else                  { 365 }
-        }
-
-        fn iso_week_days(yday: i32, wday: i32) -> i32 {
-            /* The number of days from the first day of the first ISO week of this
-            * year to the year day YDAY with week day WDAY.
-            * ISO weeks start on Monday. The first ISO week has the year's first
-            * Thursday.
-            * YDAY may be as small as yday_minimum.
-            */
-            let yday: i32 = yday as i32;
-            let wday: i32 = wday as i32;
-            let iso_week_start_wday: i32 = 1;                     /* Monday */
-            let iso_week1_wday: i32 = 4;                          /* Thursday */
-            let yday_minimum: i32 = 366;
-            /* Add enough to the first operand of % to make it nonnegative. */
-            let big_enough_multiple_of_7: i32 = (yday_minimum / 7 + 2) * 7;
-
-            yday - (yday - wday + iso_week1_wday + big_enough_multiple_of_7) % 7
-                + iso_week1_wday - iso_week_start_wday
-        }
-
         fn iso_week(fmt: &mut fmt::Formatter, ch:char, tm: &Tm) -> fmt::Result {
-            let mut year = tm.tm_year + 1900;
-            let mut days = iso_week_days(tm.tm_yday, tm.tm_wday);
-
-            if days < 0 {
-                /* This ISO week belongs to the previous year. */
-                year -= 1;
-                days = iso_week_days(tm.tm_yday + (days_in_year(year)), tm.tm_wday);
-            } else {
-                let d = iso_week_days(tm.tm_yday - (days_in_year(year)),
-                                      tm.tm_wday);
-                if 0 <= d {
-                    /* This ISO week belongs to the next year. */
-                    year += 1;
-                    days = d;
-                }
-            }
+            let (year, week) = calendar::iso_week(tm.tm_year as i64 + 1900,
+                                                  tm.tm_yday as i64, tm.tm_wday as i64);
 
             match ch {
                 'G' => write!(fmt, "{}", year),
                 'g' => write!(fmt, "{:02}", (year % 100 + 100) % 100),
-                'V' => write!(fmt, "{:02}", days / 7 + 1),
+                'V' => write!(fmt, "{:02}", week),
                 _ => Ok(())
             }
         }
@@ -718,55 +1185,21 @@ impl<'a> fmt::String for TmFmt<'a> {
                 unreachable!()
             };
             match ch {
-              'A' => match tm.tm_wday {
-                0 => "Sunday",
-                1 => "Monday",
-                2 => "Tuesday",
-                3 => "Wednesday",
-                4 => "Thursday",
-                5 => "Friday",
-                6 => "Saturday",
-                _ => return die()
+              'A' => match Weekday::from_number(tm.tm_wday) {
+                Some(day) => day.name(),
+                None => return die()
               },
-             'a' => match tm.tm_wday {
-                0 => "Sun",
-                1 => "Mon",
-                2 => "Tue",
-                3 => "Wed",
-                4 => "Thu",
-                5 => "Fri",
-                6 => "Sat",
-                _ => return die()
+              'a' => match Weekday::from_number(tm.tm_wday) {
+                Some(day) => day.abbreviation(),
+                None => return die()
               },
-              'B' => match tm.tm_mon {
-                0 => "January",
-                1 => "February",
-                2 => "March",
-                3 => "April",
-                4 => "May",
-                5 => "June",
-                6 => "July",
-                7 => "August",
-                8 => "September",
-                9 => "October",
-                10 => "November",
-                11 => "December",
-                _ => return die()
+              'B' => match Month::from_number(tm.tm_mon + 1) {
+                Some(month) => month.name(),
+                None => return die()
               },
-              'b' | 'h' => match tm.tm_mon {
-                0 => "Jan",
-                1 => "Feb",
-                2 => "Mar",
-                3 => "Apr",
-                4 => "May",
-                5 => "Jun",
-                6 => "Jul",
-                7 => "Aug",
-                8 => "Sep",
-                9 => "Oct",
-                10 => "Nov",
-                11 => "Dec",
-                _  => return die()
+              'b' | 'h' => match Month::from_number(tm.tm_mon + 1) {
+                Some(month) => month.abbreviation(),
+                None => return die()
               },
               'C' => return write!(fmt, "{:02}", (tm.tm_year + 1900) / 100),
               'c' => {
@@ -819,6 +1252,7 @@ impl<'a> fmt::String for TmFmt<'a> {
               'n' => "\n",
               'P' => if (tm.tm_hour) < 12 { "am" } else { "pm" },
               'p' => if (tm.tm_hour) < 12 { "AM" } else { "PM" },
+              'q' => return write!(fmt, "{}", tm.quarter()),
               'R' => {
                     try!(parse_type(fmt, 'H', tm));
                     try!(':'.fmt(fmt));
@@ -863,7 +1297,8 @@ impl<'a> fmt::String for TmFmt<'a> {
               'w' => return (tm.tm_wday).fmt(fmt),
               'Y' => return (tm.tm_year + 1900).fmt(fmt),
               'y' => return write!(fmt, "{:02}", (tm.tm_year + 1900) % 100),
-              'Z' => if tm.tm_utcoff == 0 { "UTC"} else { "" }, // FIXME (#2350): support locale
+              // Only reached when the zone abbreviation is unknown.
+              'Z' => if tm.tm_utcoff == 0 { "UTC"} else { "" },
               'z' => {
                 let sign = if tm.tm_utcoff > 0 { '+' } else { '-' };
                 let mut m = tm.tm_utcoff.abs() / 60;
@@ -884,7 +1319,10 @@ impl<'a> fmt::String for TmFmt<'a> {
                     match chars.next() {
                         Some('%') => {
                             // we've already validated that % always precedes another char
-                            try!(parse_type(fmt, chars.next().unwrap(), self.tm));
+                            match (chars.next().unwrap(), self.zone) {
+                                ('Z', Some(abbr)) => try!(abbr.fmt(fmt)),
+                                (ch, _) => try!(parse_type(fmt, ch, self.tm)),
+                            }
                         }
                         Some(ch) => try!(ch.fmt(fmt)),
                         None => break,
@@ -900,11 +1338,13 @@ impl<'a> fmt::String for TmFmt<'a> {
                 if self.tm.tm_utcoff == 0 {
                     TmFmt {
                         tm: self.tm,
+                        zone: None,
                         format: FmtStr("%Y-%m-%dT%H:%M:%SZ"),
                     }.fmt(fmt)
                 } else {
                     let s = TmFmt {
                         tm: self.tm,
+                        zone: None,
                         format: FmtStr("%Y-%m-%dT%H:%M:%S"),
                     };
                     let sign = if self.tm.tm_utcoff > 0 { '+' } else { '-' };
@@ -925,6 +1365,16 @@ impl<'a> fmt::Show for TmFmt<'a> {
 }
 
 /// Parses the time from the string according to the format string.
+///
+/// If the format has a day of the year (`%j`) but no day of the month, the
+/// month, day of the month and day of the week are filled in from it. A
+/// quarter (`%q`) without a month gives the first month of the quarter, and
+/// with one it must contain that month.
+///
+/// Once the date is known, the day of the week (`tm_wday`) and the day of
+/// the year (`tm_yday`) are filled in from it unless the format gave them,
+/// so that the result passes `Tm::validate`. Earlier versions left both at
+/// 0. Without a valid date they are still 0.
 pub fn strptime(s: &str, format: &str) -> Result<Tm, ParseError> {
     fn match_str(s: &str, pos: usize, needle: &str) -> bool {
         s.slice_from(pos).starts_with(needle)
@@ -941,6 +1391,18 @@ pub fn strptime(s: &str, format: &str) -> Result<Tm, ParseError> {
         None
     }
 
+    fn match_names<T: Copy, F>(ss: &str, pos: usize, values: &[T], name: F)
+      -> Option<(T, usize)> where F: Fn(&T) -> &'static str {
+        for value in values.iter() {
+            let needle = name(value);
+            if match_str(ss, pos, needle) {
+                return Some((*value, pos + needle.len()));
+            }
+        }
+
+        None
+    }
+
     fn match_digits(ss: &str, pos: usize, digits: usize, ws: bool)
       -> Option<(i32, usize)> {
         let mut pos = pos;
@@ -1015,62 +1477,20 @@ pub fn strptime(s: &str, format: &str) -> Result<Tm, ParseError> {
     fn parse_type(s: &str, pos: usize, ch: char, tm: &mut Tm)
       -> Result<usize, ParseError> {
         match ch {
-          'A' => match match_strs(s, pos, &[
-              ("Sunday", 0),
-              ("Monday", 1),
-              ("Tuesday", 2),
-              ("Wednesday", 3),
-              ("Thursday", 4),
-              ("Friday", 5),
-              ("Saturday", 6)
-          ]) {
-            Some(item) => { let (v, pos) = item; tm.tm_wday = v; Ok(pos) }
+          'A' => match match_names(s, pos, &WEEKDAYS, |day| day.name()) {
+            Some((day, pos)) => { tm.tm_wday = day.number(); Ok(pos) }
             None => Err(InvalidDay)
           },
-          'a' => match match_strs(s, pos, &[
-              ("Sun", 0),
-              ("Mon", 1),
-              ("Tue", 2),
-              ("Wed", 3),
-              ("Thu", 4),
-              ("Fri", 5),
-              ("Sat", 6)
-          ]) {
-            Some(item) => { let (v, pos) = item; tm.tm_wday = v; Ok(pos) }
+          'a' => match match_names(s, pos, &WEEKDAYS, |day| day.abbreviation()) {
+            Some((day, pos)) => { tm.tm_wday = day.number(); Ok(pos) }
             None => Err(InvalidDay)
           },
-          'B' => match match_strs(s, pos, &[
-              ("January", 0),
-              ("February", 1),
-              ("March", 2),
-              ("April", 3),
-              ("May", 4),
-              ("June", 5),
-              ("July", 6),
-              ("August", 7),
-              ("September", 8),
-              ("October", 9),
-              ("November", 10),
-              ("December", 11)
-          ]) {
-            Some(item) => { let (v, pos) = item; tm.tm_mon = v; Ok(pos) }
+          'B' => match match_names(s, pos, &MONTHS, |month| month.name()) {
+            Some((month, pos)) => { tm.tm_mon = month.number() - 1; Ok(pos) }
             None => Err(InvalidMonth)
           },
-          'b' | 'h' => match match_strs(s, pos, &[
-              ("Jan", 0),
-              ("Feb", 1),
-              ("Mar", 2),
-              ("Apr", 3),
-              ("May", 4),
-              ("Jun", 5),
-              ("Jul", 6),
-              ("Aug", 7),
-              ("Sep", 8),
-              ("Oct", 9),
-              ("Nov", 10),
-              ("Dec", 11)
-          ]) {
-            Some(item) => { let (v, pos) = item; tm.tm_mon = v; Ok(pos) }
+          'b' | 'h' => match match_names(s, pos, &MONTHS, |month| month.abbreviation()) {
+            Some((month, pos)) => { tm.tm_mon = month.number() - 1; Ok(pos) }
             None => Err(InvalidMonth)
           },
           'C' => match match_digits_in_range(s, pos, 2, false, 0,
@@ -1317,20 +1737,30 @@ pub fn strptime(s: &str, format: &str) -> Result<Tm, ParseError> {
         }
     }
 
+    // The quarter is kept apart from `tm`, as it is checked against the
+    // month once the whole string is parsed.
+    fn parse_quarter(s: &str, pos: usize, quarter: &mut i32) -> Result<usize, ParseError> {
+        match match_digits_in_range(s, pos, 1, false, 1, 4) {
+            Some((v, pos)) => { *quarter = v; Ok(pos) }
+            None => Err(InvalidMonth)
+        }
+    }
+
     let mut rdr = BufReader::new(format.as_bytes());
     let mut tm = Tm {
         tm_sec: 0,
         tm_min: 0,
         tm_hour: 0,
         tm_mday: 0,
-        tm_mon: 0,
+        tm_mon: -1,
         tm_year: 0,
-        tm_wday: 0,
-        tm_yday: 0,
+        tm_wday: -1,
+        tm_yday: -1,
         tm_isdst: 0,
         tm_utcoff: 0,
         tm_nsec: 0,
     };
+    let mut quarter = 0;
     let mut pos = 0;
     let len = s.len();
     let mut result = Err(InvalidTime);
@@ -1351,7 +1781,12 @@ pub fn strptime(s: &str, format: &str) -> Result<Tm, ParseError> {
                     Ok(..) => buf[0] as char,
                     Err(..) => break
                 };
-                match parse_type(s, pos, ch, &mut tm) {
+                let parsed = if ch == 'q' {
+                    parse_quarter(s, pos, &mut quarter)
+                } else {
+                    parse_type(s, pos, ch, &mut tm)
+                };
+                match parsed {
                     Ok(next) => pos = next,
                     Err(e) => { result = Err(e); break; }
                 }
@@ -1364,6 +1799,49 @@ pub fn strptime(s: &str, format: &str) -> Result<Tm, ParseError> {
     }
 
     if pos == len && rdr.tell().unwrap() == format.len() as u64 {
+        // A day of the year without a day of the month gives the month, the
+        // day and the day of the week.
+        if tm.tm_yday >= 0 && tm.tm_mday == 0 {
+            let date = try!(Date::from_ordinal(tm.tm_year + 1900, tm.tm_yday + 1));
+            tm.tm_mon = date.month() - 1;
+            tm.tm_mday = date.day();
+            tm.tm_wday = date.weekday().number();
+        }
+
+        // A quarter without a month gives its first month, and one with a
+        // month must contain it.
+        if quarter != 0 {
+            if tm.tm_mon < 0 {
+                tm.tm_mon = (quarter - 1) * 3;
+            } else if tm.tm_mon / 3 + 1 != quarter {
+                return Err(InvalidMonth);
+            }
+        }
+        if tm.tm_mon < 0 {
+            tm.tm_mon = 0;
+        }
+
+        // Once the date is known, the day of the week and the day of the
+        // year that were not parsed follow from it, so that the result
+        // passes `Tm::validate`.
+        let year = tm.tm_year as i64 + 1900;
+        let month = tm.tm_mon as i64 + 1;
+        if tm.tm_mday != 0 && tm.tm_mday as i64 <= calendar::days_in_month(year, month) {
+            let days = calendar::days_from_civil(year, month, tm.tm_mday as i64);
+            if tm.tm_wday < 0 {
+                tm.tm_wday = calendar::weekday_from_days(days) as i32;
+            }
+            if tm.tm_yday < 0 {
+                tm.tm_yday = (days - calendar::days_from_civil(year, 1, 1)) as i32;
+            }
+        }
+        if tm.tm_wday < 0 {
+            tm.tm_wday = 0;
+        }
+        if tm.tm_yday < 0 {
+            tm.tm_yday = 0;
+        }
+
         Ok(Tm {
             tm_sec: tm.tm_sec,
             tm_min: tm.tm_min,
@@ -1389,11 +1867,17 @@ pub fn strftime(format: &str, tm: &Tm) -> Result<String, ParseError> {
 mod tests {
     extern crate test;
     use super::{Timespec, get_time, precise_time_ns, precise_time_s, tzset,
-                at_utc, at, strptime, PreciseTime};
+                at_utc, at, at_in, at_offset, try_at_utc, try_at, try_at_offset, at_zoned,
+                strptime, PreciseTime, TimeZone, LocalResult, DayOverflow, Period,
+                Date, LocalZone, empty_tm, rustrt, MIN_YEAR, MAX_YEAR};
     use super::ParseError::{InvalidTime, InvalidYear, MissingFormatConverter,
-                            InvalidFormatSpecifier};
+                            InvalidFormatSpecifier, InvalidSecond, InvalidMinute,
+                            InvalidHour, InvalidMonth, InvalidDayOfMonth,
+                            InvalidDayOfWeek, InvalidDayOfYear, InvalidNanosecond,
+                            InvalidZoneOffset};
 
     use std::f64;
+    use std::i64;
     use std::u64;
     use std::time::Duration;
     use self::test::Bencher;
@@ -1491,6 +1975,40 @@ mod tests {
         assert_eq!(utc.tm_nsec, 54321);
     }
 
+    fn test_utc_matches_c() {
+        use std::mem;
+        use libc::time_t;
+
+        fn check(sec: i64) {
+            let time = Timespec::new(sec, 54321);
+            let mut expected = empty_tm();
+            // gmtime_s, on Windows, rejects times before 1970.
+            if unsafe { rustrt::rust_time_gmtime(sec, 54321, &mut expected) } == 0 {
+                return;
+            }
+            let utc = at_utc(time);
+            assert_eq!(utc, expected);
+            assert_eq!(utc.to_timespec().sec, unsafe { rustrt::rust_time_timegm(&utc) });
+            assert_eq!(utc.to_timespec(), time);
+        }
+
+        // Every day of the range `time_t` can hold, at a different time of
+        // day each, and the seconds around each midnight of 1969 to 1971.
+        let (first, last) = if mem::size_of::<time_t>() == 4 {
+            (-24_855, 24_854)
+        } else {
+            (-1_000_000, 1_000_000)
+        };
+        for day in first..last {
+            check(day * 86_400 + (day * 7_919 & 0xffff));
+        }
+        for day in -366..366 {
+            for &sec in [-2, -1, 0, 1].iter() {
+                check(day * 86_400 + sec);
+            }
+        }
+    }
+
     fn test_at() {
         set_time_zone();
 
@@ -1512,6 +2030,298 @@ mod tests {
         assert_eq!(local.tm_nsec, 54321);
     }
 
+    fn test_at_zoned() {
+        set_time_zone();
+
+        let time = Timespec::new(1234567890, 54321);
+        let zoned = at_zoned(time);
+        assert_eq!(zoned.tm, at(time));
+        assert_eq!(zoned.abbr, "PST".to_string());
+        assert_eq!(zoned.strftime("%Z").unwrap().to_string(), "PST".to_string());
+        assert_eq!(zoned.rfc822().to_string(), "Fri, 13 Feb 2009 15:31:30 PST".to_string());
+        assert_eq!(at_utc(time).to_local_zoned(), zoned);
+    }
+
+    fn test_at_in() {
+        set_time_zone();
+
+        let berlin = TimeZone::from_bytes(
+            include_bytes!("../tests/zoneinfo/Europe/Berlin")).unwrap();
+        let time = Timespec::new(1234567890, 54321);
+        let tm = at_in(time, &berlin);
+
+        assert_eq!(tm.tm_sec, 30);
+        assert_eq!(tm.tm_min, 31);
+        assert_eq!(tm.tm_hour, 0);
+        assert_eq!(tm.tm_mday, 14);
+        assert_eq!(tm.tm_mon, 1);
+        assert_eq!(tm.tm_year, 109);
+        assert_eq!(tm.tm_wday, 6);
+        assert_eq!(tm.tm_yday, 44);
+        assert_eq!(tm.tm_isdst, 0);
+        assert_eq!(tm.tm_utcoff, 3600);
+        assert_eq!(tm.tm_nsec, 54321);
+        assert_eq!(tm.to_timespec_in(&berlin), LocalResult::Single(time));
+
+        // The process zone is left alone.
+        assert_eq!(at(time).tm_utcoff, -28800);
+    }
+
+    fn test_at_offset() {
+        set_time_zone();
+
+        let time = Timespec::new(1234567890, 54321);
+        let tm = at_offset(time, 19800);
+
+        assert_eq!(tm.tm_sec, 30);
+        assert_eq!(tm.tm_min, 1);
+        assert_eq!(tm.tm_hour, 5);
+        assert_eq!(tm.tm_mday, 14);
+        assert_eq!(tm.tm_mon, 1);
+        assert_eq!(tm.tm_year, 109);
+        assert_eq!(tm.tm_wday, 6);
+        assert_eq!(tm.tm_yday, 44);
+        assert_eq!(tm.tm_isdst, 0);
+        assert_eq!(tm.tm_utcoff, 19800);
+        assert_eq!(tm.tm_nsec, 54321);
+
+        assert_eq!(at_offset(time, 0), at_utc(time));
+        assert_eq!(at_offset(time, -28800), at(time));
+    }
+
+    fn test_supported_range() {
+        set_time_zone();
+
+        // Hundreds of thousands of years either side of the epoch round trip.
+        let years = |n: i64| Timespec::new(n * 31_556_952, 54321);
+        for &n in [-262_000, 262_000, -1_000_000_000, 1_000_000_000].iter() {
+            let tm = at_utc(years(n));
+            assert_eq!(tm.to_timespec(), years(n));
+            assert_eq!(Date::from_tm(&tm).unwrap().year() as i64 - 1970, n);
+            assert_eq!(try_at_offset(years(n), 19800), Ok(at_offset(years(n), 19800)));
+        }
+
+        let max = Date::from_ymd(MAX_YEAR, 12, 31).unwrap().to_tm().to_timespec() +
+                  Duration::seconds(86399);
+        assert_eq!(try_at_utc(max).map(|tm| tm.tm_year + 1900), Ok(MAX_YEAR));
+        assert_eq!(try_at_utc(max + Duration::seconds(1)), Err(InvalidYear));
+        assert_eq!(try_at_offset(max, 1), Err(InvalidYear));
+        let min = Date::from_ymd(MIN_YEAR, 1, 1).unwrap().to_tm().to_timespec();
+        assert_eq!(try_at_utc(min).map(|tm| tm.tm_year + 1900), Ok(MIN_YEAR));
+        assert_eq!(try_at_utc(min - Duration::seconds(1)), Err(InvalidYear));
+
+        let far = Timespec::new(i64::MAX / 2, 0);
+        let berlin = TimeZone::from_bytes(
+            include_bytes!("../tests/zoneinfo/Europe/Berlin")).unwrap();
+        assert_eq!(try_at_utc(far), Err(InvalidYear));
+        assert_eq!(try_at(far), Err(InvalidYear));
+        assert_eq!(berlin.try_at(far), Err(InvalidYear));
+        assert_eq!(berlin.try_at(Timespec::new(i64::MIN, 0)), Err(InvalidYear));
+        assert_eq!(try_at_offset(Timespec::new(i64::MAX, 0), 86399), Err(InvalidYear));
+
+        // Local times are not limited by the C library.
+        let zone = LocalZone::new();
+        assert_eq!(try_at(max - Duration::days(1)), Ok(zone.at(max - Duration::days(1))));
+        assert_eq!(try_at(min + Duration::days(1)), Ok(zone.at(min + Duration::days(1))));
+        assert_eq!(at(years(1_000_000_000)), zone.at(years(1_000_000_000)));
+
+        let time = Timespec::new(1234567890, 54321);
+        assert_eq!(try_at(time), Ok(at(time)));
+        assert_eq!(berlin.try_at(time), Ok(at_in(time, &berlin)));
+        // Berlin is ahead of UTC, so its clocks leave the range first.
+        assert_eq!(berlin.try_at(max), Err(InvalidYear));
+        assert_eq!(berlin.try_at(min).map(|tm| tm.tm_year + 1900), Ok(MIN_YEAR));
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_at_utc_out_of_range() {
+        at_utc(Timespec::new(i64::MAX / 2, 0));
+    }
+
+    fn test_tm_add_sub() {
+        set_time_zone();
+
+        let time = Timespec::new(1234567890, 54321);
+        let local = at(time);
+
+        let later = local + Duration::hours(9);
+        assert_eq!(later.tm_utcoff, -28800);
+        assert_eq!((later.tm_mday, later.tm_hour), (14, 0));
+        assert_eq!(later.to_timespec(), time + Duration::hours(9));
+
+        let earlier = local - Duration::days(1);
+        assert_eq!(earlier.tm_utcoff, -28800);
+        assert_eq!((earlier.tm_mday, earlier.tm_hour), (12, 15));
+
+        let utc = at_utc(time) + Duration::seconds(30);
+        assert_eq!(utc.tm_utcoff, 0);
+        assert_eq!((utc.tm_min, utc.tm_sec), (32, 0));
+    }
+
+    fn test_normalize() {
+        set_time_zone();
+
+        let time = Timespec::new(1234567890, 54321);
+        let local = at(time);
+
+        let mut tm = local;
+        tm.normalize();
+        assert_eq!(tm, local);
+
+        // 2009-01-44T15:31:30-08:00
+        tm.tm_mon = 0;
+        tm.tm_mday = 44;
+        tm.tm_wday = 0;
+        tm.tm_yday = 0;
+        tm.normalize();
+        assert_eq!(tm, local);
+
+        // 2009-02-13T15:31:30 plus 45 days, 10 hours and 1.5 seconds.
+        tm.tm_mday += 45;
+        tm.tm_hour += 10;
+        tm.tm_sec += 1;
+        tm.tm_nsec += 500_000_000;
+        tm.normalize();
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (109, 2, 31));
+        assert_eq!((tm.tm_hour, tm.tm_min, tm.tm_sec, tm.tm_nsec), (1, 31, 31, 500_054_321));
+        assert_eq!((tm.tm_wday, tm.tm_yday), (2, 89));
+        assert_eq!(tm.tm_utcoff, -28800);
+
+        // Negative fields borrow: 2009-00-00T-1:00:60.
+        let mut tm = at_utc(Timespec::new(0, 0));
+        tm.tm_year = 109;
+        tm.tm_mon = 0;
+        tm.tm_mday = 0;
+        tm.tm_hour = -1;
+        tm.tm_sec = 60;
+        tm.tm_nsec = -1;
+        tm.normalize();
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (108, 11, 30));
+        assert_eq!((tm.tm_hour, tm.tm_min, tm.tm_sec, tm.tm_nsec), (23, 0, 59, 999_999_999));
+        assert_eq!((tm.tm_wday, tm.tm_yday), (2, 364));
+    }
+
+    fn test_add_months() {
+        set_time_zone();
+
+        // 2009-01-31T15:31:30-08:00
+        let local = at(Timespec::new(1233444690, 54321));
+
+        let tm = local.add_months(1, DayOverflow::Clamp);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (109, 1, 28));
+        assert_eq!((tm.tm_hour, tm.tm_min, tm.tm_sec, tm.tm_nsec), (15, 31, 30, 54321));
+        assert_eq!((tm.tm_wday, tm.tm_yday, tm.tm_utcoff), (6, 58, -28800));
+        assert_eq!(tm.validate(), Ok(()));
+
+        let tm = local.add_months(1, DayOverflow::Overflow);
+        assert_eq!((tm.tm_mon, tm.tm_mday, tm.tm_wday), (2, 3, 2));
+        let tm = local.add_months(13, DayOverflow::Clamp);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (110, 1, 28));
+        let tm = local.sub_months(2, DayOverflow::Clamp);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (108, 10, 30));
+        assert_eq!(local.add_months(-2, DayOverflow::Clamp), tm);
+
+        let leap = local.add_years(-1, DayOverflow::Clamp).add_months(1, DayOverflow::Clamp);
+        assert_eq!((leap.tm_year, leap.tm_mon, leap.tm_mday), (108, 1, 29));
+        let tm = leap.add_years(1, DayOverflow::Clamp);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (109, 1, 28));
+        let tm = leap.sub_years(3, DayOverflow::Overflow);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (105, 2, 1));
+
+        // January 40 is February 9, so a month later is March 9.
+        let mut unnormalized = local;
+        unnormalized.tm_mday = 40;
+        let tm = unnormalized.add_months(1, DayOverflow::Clamp);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (109, 2, 9));
+        let tm = unnormalized.add_years(1, DayOverflow::Clamp);
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (110, 1, 9));
+    }
+
+    fn test_days_until() {
+        set_time_zone();
+
+        // 2014-03-08T12:00:00-08:00, the day before daylight saving time
+        // started, and 2014-03-09T23:30:00-07:00, after a 23-hour day.
+        let before = at(Timespec::new(1394308800, 0));
+        let after = at(Timespec::new(1394433000, 0));
+        assert_eq!(before.days_until(&after), 1);
+        assert_eq!(after.days_until(&before), -1);
+        assert_eq!(before.days_until(&before), 0);
+        assert_eq!((after - Duration::hours(23)).days_until(&after), 0);
+
+        let period = before.period_until(&after.add_years(2, DayOverflow::Clamp));
+        assert_eq!(period, Period { years: 2, months: 0, days: 1 });
+
+        // Unnormalized fields are carried first: 2014-01-35 is February 4.
+        let mut tm = before;
+        tm.tm_mon = 0;
+        tm.tm_mday = 35;
+        assert_eq!(tm.period_until(&before), Period { years: 0, months: 1, days: 4 });
+        assert_eq!(tm.days_until(&before), 32);
+    }
+
+    fn test_validate() {
+        set_time_zone();
+
+        let local = at(Timespec::new(1234567890, 54321));
+        assert_eq!(local.validate(), Ok(()));
+        assert_eq!(empty_tm().validate(), Err(InvalidDayOfMonth));
+
+        let mut tm = local;
+        tm.tm_sec = 60;
+        assert_eq!(tm.validate(), Ok(()));
+        let mut tm = local;
+        tm.tm_sec = 61;
+        assert_eq!(tm.validate(), Err(InvalidSecond));
+        let mut tm = local;
+        tm.tm_min = -1;
+        assert_eq!(tm.validate(), Err(InvalidMinute));
+        let mut tm = local;
+        tm.tm_hour = 24;
+        assert_eq!(tm.validate(), Err(InvalidHour));
+        let mut tm = local;
+        tm.tm_mon = 12;
+        assert_eq!(tm.validate(), Err(InvalidMonth));
+        let mut tm = local;
+        tm.tm_mday = 29;
+        assert_eq!(tm.validate(), Err(InvalidDayOfMonth));
+        let mut tm = local;
+        tm.tm_wday = 4;
+        assert_eq!(tm.validate(), Err(InvalidDayOfWeek));
+        let mut tm = local;
+        tm.tm_yday = 0;
+        assert_eq!(tm.validate(), Err(InvalidDayOfYear));
+        let mut tm = local;
+        tm.tm_nsec = 1_000_000_000;
+        assert_eq!(tm.validate(), Err(InvalidNanosecond));
+        let mut tm = local;
+        tm.tm_utcoff = 86_400;
+        assert_eq!(tm.validate(), Err(InvalidZoneOffset));
+        let mut tm = local;
+        tm.tm_isdst = -1;
+        assert_eq!(tm.validate(), Ok(()));
+
+        // February 29 only exists in leap years.
+        let leap = strptime("2012-02-29 Wed 060", "%Y-%m-%d %a %j").unwrap();
+        assert_eq!(leap.validate(), Ok(()));
+        let mut tm = leap;
+        tm.tm_year = 113;
+        assert_eq!(tm.validate(), Err(InvalidDayOfMonth));
+
+        assert_eq!(tm.strftime("%F").unwrap_err(), InvalidDayOfMonth);
+        assert_eq!(empty_tm().strftime("%s").unwrap_err(), InvalidDayOfMonth);
+
+        // Conversions of single fields only check those fields.
+        assert_eq!(tm.strftime("%Y %H:%M").unwrap().to_string(), "2013 00:00".to_string());
+        let mut tm = local;
+        tm.tm_hour = 24;
+        assert_eq!(tm.strftime("%Y-%m-%d").unwrap().to_string(), "2009-02-13".to_string());
+        assert_eq!(tm.strftime("%R").unwrap_err(), InvalidHour);
+        tm.tm_wday = 7;
+        assert_eq!(tm.strftime("%a").unwrap_err(), InvalidDayOfWeek);
+    }
+
     fn test_to_timespec() {
         set_time_zone();
 
@@ -1520,6 +2330,66 @@ mod tests {
 
         assert_eq!(utc.to_timespec(), time);
         assert_eq!(utc.to_local().to_timespec(), time);
+
+        // Parsed offsets are honored even though they differ from the
+        // process zone.
+        let format = "%Y-%m-%d %H:%M:%S %z";
+        let tokyo = strptime("2009-02-14 08:31:30 +0900", format).unwrap();
+        assert_eq!(tokyo.to_timespec(), Timespec::new(1234567890, 0));
+        let india = strptime("2009-02-14 05:01:30 +0530", format).unwrap();
+        assert_eq!(india.to_timespec(), Timespec::new(1234567890, 0));
+        let new_york = strptime("2009-02-13 18:31:30 -0500", format).unwrap();
+        assert_eq!(new_york.to_timespec(), Timespec::new(1234567890, 0));
+
+        // `tm_isdst` does not shift the result.
+        let mut local = at(time);
+        local.tm_isdst = 1;
+        assert_eq!(local.to_timespec(), time);
+
+        // Out-of-range fields are carried: 2009-01-44T23:31:30Z.
+        let mut carried = utc;
+        carried.tm_mon = 0;
+        carried.tm_mday = 44;
+        assert_eq!(carried.to_timespec(), time);
+    }
+
+    fn test_day_counts() {
+        set_time_zone();
+
+        let epoch = Timespec::new(0, 0);
+        assert_eq!(epoch.julian_day(), 2440587.5);
+        assert_eq!(epoch.modified_julian_date(), 40587.0);
+        assert_eq!(epoch.rata_die(), 719163.0);
+
+        // J2000.0 is noon UTC on 2000-01-01.
+        let j2000 = Timespec::new(946728000, 0);
+        assert_eq!(j2000.julian_day(), 2451545.0);
+        assert_eq!(at_utc(j2000).julian_day(), 2451545.0);
+        assert_eq!(at(j2000).julian_day(), 2451545.0);
+        assert_eq!(at(j2000).modified_julian_date(), 51544.5);
+        assert_eq!(at(j2000).rata_die(), 730120.5);
+        assert_eq!(Timespec::from_julian_day(2451545.0), j2000);
+        assert_eq!(Timespec::from_julian_day(2451544.5), Timespec::new(946684800, 0));
+        assert_eq!(Timespec::from_modified_julian_date(54875.25),
+                   Timespec::new(1234483200 + 6 * 3600, 0));
+        assert_eq!(Timespec::from_rata_die(1.0), Timespec::new(-62135596800, 0));
+
+        // The split form keeps every nanosecond.
+        let time = Timespec::new(1234567890, 54321);
+        assert_eq!(time.modified_julian_day_and_nanos(), (54875, 84690000054321));
+        assert_eq!(Timespec::from_modified_julian_day_and_nanos(54875, 84690000054321), time);
+        assert_eq!(Timespec::new(-1, 5).modified_julian_day_and_nanos(),
+                   (40586, 86399000000005));
+        assert_eq!(Timespec::from_modified_julian_day_and_nanos(40587, -1),
+                   Timespec::new(-1, 999999999));
+
+        // Fractional days are only as precise as an `f64` allows.
+        let error = Timespec::from_modified_julian_date(time.modified_julian_date()) - time;
+        assert!(error.num_microseconds().unwrap().abs() <= 1);
+        let error = Timespec::from_julian_day(time.julian_day()) - time;
+        assert!(error.num_microseconds().unwrap().abs() <= 50);
+        let error = Timespec::from_rata_die(time.rata_die()) - time;
+        assert!(error.num_microseconds().unwrap().abs() <= 50);
     }
 
     fn test_conversions() {
@@ -1571,7 +2441,7 @@ mod tests {
             assert!(tm.tm_mon == 1);
             assert!(tm.tm_year == 109);
             assert!(tm.tm_wday == 5);
-            assert!(tm.tm_yday == 0);
+            assert!(tm.tm_yday == 43);
             assert!(tm.tm_isdst == 0);
             assert!(tm.tm_utcoff == 0);
             assert!(tm.tm_nsec == 12340000);
@@ -1673,6 +2543,8 @@ mod tests {
         assert!(test("pm", "%P"));
         assert!(test("AM", "%p"));
         assert!(test("PM", "%p"));
+        assert!(test("1", "%q"));
+        assert_eq!(strptime("5", "%q"), Err(InvalidMonth));
         assert!(test("23:31", "%R"));
         assert!(test("11:31:30 AM", "%r"));
         assert!(test("11:31:30 PM", "%r"));
@@ -1696,6 +2568,35 @@ mod tests {
 
         // Test for #7256
         assert_eq!(strptime("360", "%Y-%m-%d"), Err(InvalidYear));
+
+        // A day of the year resolves to a full date.
+        let tm = strptime("2009-044 23:31:30", "%Y-%j %T").unwrap();
+        assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (109, 1, 13));
+        assert_eq!((tm.tm_wday, tm.tm_yday), (5, 43));
+        assert_eq!(tm.to_timespec(), Timespec::new(1234567890, 0));
+        let tm = strptime("2012 366", "%Y %j").unwrap();
+        assert_eq!((tm.tm_mon, tm.tm_mday, tm.tm_wday), (11, 31, 1));
+        assert_eq!(strptime("2009 366", "%Y %j"), Err(InvalidDayOfYear));
+
+        // The day of the week and of the year follow from a parsed date, so
+        // the result can be formatted again.
+        let tm = strptime("2009-02-13", "%Y-%m-%d").unwrap();
+        assert_eq!((tm.tm_wday, tm.tm_yday), (5, 43));
+        assert_eq!(tm.validate(), Ok(()));
+        assert_eq!(tm.strftime("%Y-%m-%d").unwrap().to_string(), "2009-02-13".to_string());
+        assert_eq!(tm.strftime("%a %j %F").unwrap().to_string(),
+                   "Fri 044 2009-02-13".to_string());
+        let tm = strptime("2009-02-30", "%Y-%m-%d").unwrap();
+        assert_eq!(tm.strftime("%F").unwrap_err(), InvalidDayOfMonth);
+
+        // A quarter gives its first month, unless a month in it is given.
+        let tm = strptime("Q3 2026", "Q%q %Y").unwrap();
+        assert_eq!((tm.tm_year, tm.tm_mon), (126, 6));
+        let tm = strptime("2026-Q4-11", "%Y-Q%q-%m").unwrap();
+        assert_eq!(tm.tm_mon, 10);
+        assert_eq!(strptime("2026-Q4-03", "%Y-Q%q-%m"), Err(InvalidMonth));
+        assert_eq!(strptime("2026-Q1 100", "%Y-Q%q %j"), Err(InvalidMonth));
+        assert_eq!(strptime("2026 100", "%Y %j").map(|tm| tm.tm_mon), Ok(3));
     }
 
     fn test_asctime() {
@@ -1757,6 +2658,7 @@ mod tests {
         assert_eq!(local.strftime("%n").unwrap().to_string(), "\n".to_string());
         assert_eq!(local.strftime("%P").unwrap().to_string(), "pm".to_string());
         assert_eq!(local.strftime("%p").unwrap().to_string(), "PM".to_string());
+        assert_eq!(local.strftime("%q").unwrap().to_string(), "1".to_string());
         assert_eq!(local.strftime("%R").unwrap().to_string(), "15:31".to_string());
         assert_eq!(local.strftime("%r").unwrap().to_string(), "03:31:30 PM".to_string());
         assert_eq!(local.strftime("%S").unwrap().to_string(), "30".to_string());
@@ -1775,14 +2677,14 @@ mod tests {
         assert_eq!(local.strftime("%x").unwrap().to_string(), "02/13/09".to_string());
         assert_eq!(local.strftime("%Y").unwrap().to_string(), "2009".to_string());
         assert_eq!(local.strftime("%y").unwrap().to_string(), "09".to_string());
-        // FIXME (#2350): support locale
+        // The abbreviation is only known for a `ZonedTm`.
         assert_eq!(local.strftime("%Z").unwrap().to_string(), "".to_string());
         assert_eq!(local.strftime("%z").unwrap().to_string(), "-0800".to_string());
         assert_eq!(local.strftime("%+").unwrap().to_string(),
                    "2009-02-13T15:31:30-08:00".to_string());
         assert_eq!(local.strftime("%%").unwrap().to_string(), "%".to_string());
 
-         let invalid_specifiers = ["%E", "%J", "%K", "%L", "%N", "%O", "%o", "%Q", "%q"];
+         let invalid_specifiers = ["%E", "%J", "%K", "%L", "%N", "%O", "%o", "%Q"];
         for &sp in invalid_specifiers.iter() {
             assert_eq!(local.strftime(sp).unwrap_err(), InvalidFormatSpecifier(sp.char_at(1)));
         }
@@ -1791,6 +2693,7 @@ mod tests {
 
         assert_eq!(local.asctime().to_string(), "Fri Feb 13 15:31:30 2009".to_string());
         assert_eq!(local.ctime().to_string(), "Fri Feb 13 15:31:30 2009".to_string());
+        assert_eq!(local.rfc822().to_string(), "Fri, 13 Feb 2009 15:31:30 ".to_string());
         assert_eq!(local.rfc822z().to_string(), "Fri, 13 Feb 2009 15:31:30 -0800".to_string());
         assert_eq!(local.rfc3339().to_string(), "2009-02-13T15:31:30-08:00".to_string());
 
@@ -1884,8 +2787,19 @@ mod tests {
         test_precise_time();
         test_precise_time_to();
         test_at_utc();
+        test_utc_matches_c();
         test_at();
+        test_at_zoned();
+        test_at_in();
+        test_at_offset();
+        test_supported_range();
+        test_tm_add_sub();
+        test_normalize();
+        test_validate();
+        test_add_months();
+        test_days_until();
         test_to_timespec();
+        test_day_counts();
         test_conversions();
         test_strptime();
         test_asctime();
@@ -0,0 +1,229 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The local time zone, read once and independent of the C library's global
+//! time zone state.
+
+use std::mem;
+use std::os;
+use std::path::Path;
+use std::sync::{Arc, Once, ONCE_INIT};
+
+use super::{Timespec, Tm, ZonedTm, ParseError, get_time};
+use zoneinfo::{self, TimeZone, ZoneError, Disambiguate};
+
+/// The file describing the system's local time zone when `TZ` is not set.
+static LOCALTIME: &'static str = "/etc/localtime";
+
+/// A snapshot of the local time zone.
+///
+/// The zone is read once, when the `LocalZone` is created, from the `TZ`
+/// environment variable or `/etc/localtime`. Conversions never consult `TZ`
+/// again nor call `tzset()`, so a `LocalZone` can be shared between threads
+/// while other threads change the environment. Create a new `LocalZone` to
+/// pick up changes to the local time zone.
+#[derive(Clone, Show)]
+pub struct LocalZone {
+    zone: Arc<TimeZone>,
+}
+
+impl LocalZone {
+    /// Reads the local time zone.
+    ///
+    /// Like the C library, this falls back to UTC if `TZ` names a zone that
+    /// cannot be loaded, or if `TZ` is unset and `/etc/localtime` cannot be
+    /// read.
+    pub fn new() -> LocalZone {
+        LocalZone::from_zone(read_local_zone().unwrap_or_else(|_| TimeZone::utc()))
+    }
+
+    /// Reads the local time zone described by `tz`, a value of the `TZ`
+    /// environment variable.
+    ///
+    /// An empty string means UTC. Otherwise, after stripping a leading `:`,
+    /// an absolute path is read as a TZif file and anything else is looked up
+    /// as a zone name, such as "Europe/Berlin". Values without a leading `:`
+    /// that do not name a zone are parsed as POSIX TZ strings, such as
+    /// "CET-1CEST,M3.5.0,M10.5.0/3".
+    pub fn from_tz(tz: &str) -> Result<LocalZone, ZoneError> {
+        zone_from_tz(tz, &zoneinfo::tzdir()).map(LocalZone::from_zone)
+    }
+
+    /// Uses `zone` as the local time zone.
+    pub fn from_zone(zone: TimeZone) -> LocalZone {
+        LocalZone { zone: Arc::new(zone) }
+    }
+
+    /// Returns the rules of the local time zone.
+    pub fn zone(&self) -> &TimeZone {
+        &*self.zone
+    }
+
+    /// Returns the specified time in the local time zone.
+    ///
+    /// Panics if the year is outside `[MIN_YEAR, MAX_YEAR]`.
+    pub fn at(&self, clock: Timespec) -> Tm {
+        self.zone.at(clock)
+    }
+
+    /// Returns the specified time in the local time zone, or
+    /// `Err(InvalidYear)` if the year is outside `[MIN_YEAR, MAX_YEAR]`.
+    pub fn try_at(&self, clock: Timespec) -> Result<Tm, ParseError> {
+        self.zone.try_at(clock)
+    }
+
+    /// Returns the current time in the local time zone.
+    pub fn now(&self) -> Tm {
+        self.at(get_time())
+    }
+
+    /// Returns the specified time in the local time zone, together with the
+    /// zone abbreviation.
+    pub fn at_zoned(&self, clock: Timespec) -> ZonedTm {
+        self.zone.at_zoned(clock)
+    }
+
+    /// Converts the wall-clock time in `tm`, read in the local time zone, to
+    /// the seconds from January 1, 1970. `tm_utcoff` is ignored.
+    ///
+    /// A wall-clock time that is repeated by a transition resolves to the
+    /// earlier instant, and one that is skipped is moved forward by the length
+    /// of the gap. Use `to_timespec_with` or `TimeZone::local_to_timespec` to
+    /// handle these cases explicitly.
+    pub fn to_timespec(&self, tm: &Tm) -> Timespec {
+        self.zone.local_to_timespec_with(tm, Disambiguate::ShiftForward).unwrap()
+    }
+
+    /// Converts the wall-clock time in `tm`, read in the local time zone, to
+    /// the seconds from January 1, 1970, resolving skipped and repeated times
+    /// according to `policy`.
+    pub fn to_timespec_with(&self, tm: &Tm, policy: Disambiguate) -> Option<Timespec> {
+        self.zone.local_to_timespec_with(tm, policy)
+    }
+}
+
+/// Returns the local time zone that `at` and `at_zoned` use for the times the
+/// C library cannot convert, read on first use, or `None` if it could not be
+/// loaded. Unlike `LocalZone::new`, this does not fall back to UTC, which
+/// the C library may not have used.
+pub fn fallback() -> Option<&'static LocalZone> {
+    static mut FALLBACK: *const LocalZone = 0 as *const LocalZone;
+    static ONCE: Once = ONCE_INIT;
+    unsafe {
+        ONCE.call_once(|| {
+            if let Ok(zone) = read_local_zone() {
+                FALLBACK = mem::transmute(Box::new(LocalZone::from_zone(zone)));
+            }
+        });
+        FALLBACK.as_ref()
+    }
+}
+
+fn read_local_zone() -> Result<TimeZone, ZoneError> {
+    match os::getenv("TZ") {
+        Some(tz) => zone_from_tz(tz.as_slice(), &zoneinfo::tzdir()),
+        None => TimeZone::from_file(&Path::new(LOCALTIME)),
+    }
+}
+
+fn zone_from_tz(tz: &str, tzdir: &Path) -> Result<TimeZone, ZoneError> {
+    if tz.is_empty() {
+        return Ok(TimeZone::utc());
+    }
+    let name = if tz.starts_with(":") { tz.slice_from(1) } else { tz };
+    if name.starts_with("/") {
+        return TimeZone::from_file(&Path::new(name));
+    }
+    match TimeZone::named_in(tzdir, name) {
+        Err(..) if name.len() == tz.len() => TimeZone::from_tz_string(tz),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocalZone, zone_from_tz};
+    use std::path::Path;
+    use zoneinfo::{TimeZone, Disambiguate};
+    use zoneinfo::ZoneError::{Io, UnknownZone, InvalidTzString};
+    use {Timespec, at_utc};
+
+    fn from_tz(tz: &str) -> LocalZone {
+        LocalZone::from_zone(zone_from_tz(tz, &Path::new("tests/zoneinfo")).unwrap())
+    }
+
+    #[test]
+    fn test_from_tz() {
+        let dir = Path::new("tests/zoneinfo");
+        let berlin = TimeZone::named_in(&dir, "Europe/Berlin").unwrap();
+
+        assert_eq!(zone_from_tz("Europe/Berlin", &dir), Ok(berlin.clone()));
+        assert_eq!(zone_from_tz(":Europe/Berlin", &dir), Ok(berlin.clone()));
+        assert_eq!(zone_from_tz("", &dir), Ok(TimeZone::utc()));
+        assert_eq!(zone_from_tz("CET-1CEST,M3.5.0,M10.5.0/3", &dir),
+                   TimeZone::from_tz_string("CET-1CEST,M3.5.0,M10.5.0/3"));
+
+        assert_eq!(zone_from_tz(":CET-1CEST,M3.5.0,M10.5.0/3", &dir), Err(UnknownZone));
+        assert_eq!(zone_from_tz("Europe/Atlantis", &dir), Err(InvalidTzString));
+        assert_eq!(zone_from_tz(":/nonexistent/zoneinfo/Europe/Berlin", &dir), Err(Io));
+    }
+
+    #[test]
+    fn test_at() {
+        let time = Timespec::new(1234567890, 54321);
+
+        let local = from_tz("America/Los_Angeles").at(time);
+        assert_eq!((local.tm_mday, local.tm_hour, local.tm_min), (13, 15, 31));
+        assert_eq!(local.tm_utcoff, -28800);
+        assert_eq!(local.tm_nsec, 54321);
+
+        let local = from_tz("JST-9").at(time);
+        assert_eq!((local.tm_mday, local.tm_hour), (14, 8));
+        assert_eq!(local.tm_utcoff, 32400);
+
+        assert_eq!(from_tz("").at(time), at_utc(time));
+
+        let zoned = from_tz("America/Los_Angeles").at_zoned(time);
+        assert_eq!(zoned.rfc822().to_string(), "Fri, 13 Feb 2009 15:31:30 PST".to_string());
+        assert_eq!(from_tz("JST-9").at_zoned(time).abbr, "JST".to_string());
+        assert_eq!(from_tz("").at_zoned(time).rfc822().to_string(),
+                   "Fri, 13 Feb 2009 23:31:30 GMT".to_string());
+    }
+
+    #[test]
+    fn test_to_timespec() {
+        let zone = from_tz("America/Los_Angeles");
+        // 2009-02-13T23:31:30Z, 2014-07-01T12:00:00Z and around the DST
+        // transitions of 2014.
+        for &sec in [1234567890, 1404216000, 1394359199, 1394359200,
+                     1414915199, 1414915200, 1414922400].iter() {
+            let time = Timespec::new(sec, 54321);
+            assert_eq!(zone.to_timespec(&zone.at(time)), time);
+        }
+
+        // 01:00 PST on 2014-11-02 is repeated and resolves to 01:00 PDT.
+        let tm = zone.at(Timespec::new(1414918800, 0));
+        assert_eq!(zone.to_timespec(&tm), Timespec::new(1414915200, 0));
+        assert_eq!(zone.to_timespec_with(&tm, Disambiguate::Latest),
+                   Some(Timespec::new(1414918800, 0)));
+        assert_eq!(zone.to_timespec_with(&tm, Disambiguate::Error), None);
+
+        // `tm_utcoff` is ignored.
+        let mut tm = zone.at(Timespec::new(1234567890, 0));
+        tm.tm_utcoff = 0;
+        assert_eq!(zone.to_timespec(&tm), Timespec::new(1234567890, 0));
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<LocalZone>();
+    }
+}
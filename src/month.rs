@@ -0,0 +1,155 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Months of the year.
+
+use std::ascii::AsciiExt;
+use std::fmt;
+use std::str::FromStr;
+
+use self::Month::{January, February, March, April, May, June, July, August,
+                  September, October, November, December};
+
+/// A month of the year.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Show)]
+pub enum Month {
+    January,
+    February,
+    March,
+    April,
+    May,
+    June,
+    July,
+    August,
+    September,
+    October,
+    November,
+    December,
+}
+
+/// Every month of the year, in order.
+pub static MONTHS: [Month; 12] = [January, February, March, April, May, June, July,
+                                  August, September, October, November, December];
+
+impl Month {
+    /// Returns the month numbered `n`, with 1 being January and 12 December.
+    /// `Tm::tm_mon` is one less than this number.
+    pub fn from_number(n: i32) -> Option<Month> {
+        if n >= 1 && n <= 12 { Some(MONTHS[(n - 1) as usize]) } else { None }
+    }
+
+    /// Returns the number of the month, in `[1, 12]` with 1 being January.
+    pub fn number(&self) -> i32 {
+        *self as i32 + 1
+    }
+
+    /// Returns the following month, wrapping from December to January.
+    pub fn succ(&self) -> Month {
+        self.add_months(1)
+    }
+
+    /// Returns the previous month, wrapping from January to December.
+    pub fn pred(&self) -> Month {
+        self.add_months(-1)
+    }
+
+    /// Returns the month `months` months later, or earlier for negative
+    /// `months`, ignoring the year.
+    pub fn add_months(&self, months: i64) -> Month {
+        let n = (*self as i64 + months % 12 + 12) % 12;
+        MONTHS[n as usize]
+    }
+
+    /// Returns the English name of the month, such as "January".
+    pub fn name(&self) -> &'static str {
+        match *self {
+            January => "January",
+            February => "February",
+            March => "March",
+            April => "April",
+            May => "May",
+            June => "June",
+            July => "July",
+            August => "August",
+            September => "September",
+            October => "October",
+            November => "November",
+            December => "December",
+        }
+    }
+
+    /// Returns the three-letter English abbreviation of the month, such as
+    /// "Jan".
+    pub fn abbreviation(&self) -> &'static str {
+        self.name().slice_to(3)
+    }
+}
+
+impl FromStr for Month {
+    /// Parses the full or abbreviated English name of a month, ignoring
+    /// ASCII case.
+    fn from_str(s: &str) -> Option<Month> {
+        MONTHS.iter().map(|&month| month).find(|month| {
+            s.eq_ignore_ascii_case(month.name()) || s.eq_ignore_ascii_case(month.abbreviation())
+        })
+    }
+}
+
+impl fmt::String for Month {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Month, MONTHS};
+    use super::Month::{January, February, May, September, December};
+
+    #[test]
+    fn test_numbers() {
+        for (i, &month) in MONTHS.iter().enumerate() {
+            assert_eq!(month.number(), i as i32 + 1);
+            assert_eq!(Month::from_number(i as i32 + 1), Some(month));
+        }
+        assert_eq!(Month::from_number(0), None);
+        assert_eq!(Month::from_number(13), None);
+        assert!(January < December);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(December.succ(), January);
+        assert_eq!(January.pred(), December);
+        assert_eq!(January.succ(), February);
+        assert_eq!(May.add_months(-17), December);
+        assert_eq!(May.add_months(12 * 100 + 4), September);
+
+        let mut month = January;
+        for &expected in MONTHS.iter() {
+            assert_eq!(month, expected);
+            month = month.succ();
+        }
+        assert_eq!(month, January);
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(September.name(), "September");
+        assert_eq!(September.abbreviation(), "Sep");
+        assert_eq!(May.abbreviation(), "May");
+        assert_eq!(September.to_string(), "September".to_string());
+
+        assert_eq!("September".parse::<Month>(), Some(September));
+        assert_eq!("sep".parse::<Month>(), Some(September));
+        assert_eq!("MAY".parse::<Month>(), Some(May));
+        assert_eq!("Sept".parse::<Month>(), None);
+    }
+}
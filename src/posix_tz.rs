@@ -0,0 +1,458 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! POSIX TZ strings, such as `CET-1CEST,M3.5.0,M10.5.0/3`.
+//!
+//! The syntax is described in the POSIX description of the `TZ` environment
+//! variable. The RFC 8536 extensions used in the footer of TZif files are
+//! also accepted: transition times may be negative or exceed 24 hours.
+
+use std::cmp;
+use std::num::Int;
+
+use super::{MIN_YEAR, MAX_YEAR};
+use calendar;
+use zoneinfo::{LocalTimeType, ZoneError};
+use zoneinfo::ZoneError::InvalidTzString;
+
+/// The rule used when a DST name is given without transition dates: the
+/// United States rules since 2007, `M3.2.0,M11.1.0`.
+static DEFAULT_RULE: (RuleDate, RuleDate) = (RuleDate::MonthWeekDay(3, 2, 0),
+                                             RuleDate::MonthWeekDay(11, 1, 0));
+
+/// Transitions happen at 02:00:00 local time unless stated otherwise.
+static DEFAULT_TIME: i32 = 2 * 3600;
+
+/// The day of the year on which a transition happens.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+enum RuleDate {
+    /// `Jn`: the Julian day `n` in `[1, 365]`, never counting February 29.
+    Julian1(i64),
+    /// `n`: the zero-based day of the year in `[0, 365]`, counting
+    /// February 29 in leap years.
+    Julian0(i64),
+    /// `Mm.w.d`: day `d` (0 = Sunday) of week `w` of month `m`, where week 5
+    /// means the last such day of the month.
+    MonthWeekDay(i64, i64, i64),
+}
+
+impl RuleDate {
+    /// Returns the number of days from 1970-01-01 to this date in `year`.
+    fn days_in(&self, year: i64) -> i64 {
+        let jan1 = calendar::days_from_civil(year, 1, 1);
+        match *self {
+            RuleDate::Julian1(n) => {
+                let leap_day = if calendar::is_leap_year(year) && n >= 60 { 1 } else { 0 };
+                jan1 + n - 1 + leap_day
+            }
+            RuleDate::Julian0(n) => jan1 + n,
+            RuleDate::MonthWeekDay(month, week, wday) => {
+                let first = calendar::days_from_civil(year, month, 1);
+                let first_wday = calendar::weekday_from_days(first);
+                let mut mday = 1 + calendar::mod_floor(wday - first_wday, 7) + (week - 1) * 7;
+                if mday > calendar::days_in_month(year, month) {
+                    mday -= 7;
+                }
+                first + mday - 1
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Show)]
+struct DstRule {
+    dst: LocalTimeType,
+    start: RuleDate,
+    /// Seconds after local midnight, in standard time.
+    start_time: i32,
+    end: RuleDate,
+    /// Seconds after local midnight, in daylight saving time.
+    end_time: i32,
+}
+
+/// A time zone described by a POSIX TZ string: a standard time and,
+/// optionally, a daylight saving time with yearly transition rules.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct PosixTz {
+    std: LocalTimeType,
+    dst: Option<DstRule>,
+}
+
+impl PosixTz {
+    /// Parses a POSIX TZ string.
+    pub fn parse(s: &str) -> Result<PosixTz, ZoneError> {
+        let mut p = Parser { s: s.as_bytes(), pos: 0 };
+        let tz = try!(p.parse_tz());
+        if p.pos != p.s.len() {
+            return Err(InvalidTzString);
+        }
+        Ok(tz)
+    }
+
+    /// Returns the local time type in effect at `sec` seconds since the
+    /// epoch.
+    pub fn local_time_type(&self, sec: i64) -> &LocalTimeType {
+        let rule = match self.dst {
+            Some(ref rule) => rule,
+            None => return &self.std,
+        };
+        let (start, end) = self.dst_transitions(self.year_of(sec)).unwrap();
+        let isdst = if start < end {
+            start <= sec && sec < end
+        } else {
+            // Daylight saving time spans the new year, as in the southern
+            // hemisphere.
+            !(end <= sec && sec < start)
+        };
+        if isdst { &rule.dst } else { &self.std }
+    }
+
+    /// Returns the first instant at or after `sec` seconds since the epoch at
+    /// which local time changes, or `None` if it never does.
+    pub fn next_transition(&self, sec: i64) -> Option<i64> {
+        if self.dst.is_none() {
+            return None;
+        }
+        let year = self.year_of(sec);
+        let mut next = None;
+        // Each year has two transitions, so one of these is at or after `sec`
+        // unless `sec` is past the supported range.
+        for y in cmp::max(year - 1, MIN_YEAR as i64)..cmp::min(year + 2, MAX_YEAR as i64 + 1) {
+            let (start, end) = self.dst_transitions(y).unwrap();
+            for &t in [start, end].iter() {
+                if t >= sec && next.map_or(true, |n| t < n) {
+                    next = Some(t);
+                }
+            }
+        }
+        // A zone that observes daylight saving time all year round has
+        // transitions that change nothing.
+        next.and_then(|t| {
+            if self.local_time_type(t - 1) == self.local_time_type(t) { None } else { Some(t) }
+        })
+    }
+
+    /// Returns the start and end of daylight saving time in `year`, in
+    /// seconds since the epoch, or `None` if this zone has no daylight saving
+    /// time.
+    fn dst_transitions(&self, year: i64) -> Option<(i64, i64)> {
+        self.dst.as_ref().map(|rule| {
+            let start = rule.start.days_in(year) * calendar::SECS_PER_DAY +
+                        rule.start_time as i64 - self.std.utcoff as i64;
+            let end = rule.end.days_in(year) * calendar::SECS_PER_DAY +
+                      rule.end_time as i64 - rule.dst.utcoff as i64;
+            (start, end)
+        })
+    }
+
+    /// Returns the year, in standard time, of `sec` seconds since the epoch,
+    /// clamped to `[MIN_YEAR, MAX_YEAR]` so that the rule dates of the years
+    /// around it can be computed without overflowing.
+    fn year_of(&self, sec: i64) -> i64 {
+        let local = sec.saturating_add(self.std.utcoff as i64);
+        let year = calendar::civil_from_days(calendar::div_floor(local, calendar::SECS_PER_DAY)).0;
+        cmp::min(cmp::max(year, MIN_YEAR as i64), MAX_YEAR as i64)
+    }
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        if self.pos < self.s.len() { Some(self.s[self.pos]) } else { None }
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_tz(&mut self) -> Result<PosixTz, ZoneError> {
+        let std_abbr = try!(self.parse_name());
+        let std_utcoff = -try!(self.parse_offset());
+        let std = LocalTimeType { utcoff: std_utcoff, isdst: false, abbr: std_abbr };
+        if self.peek().is_none() {
+            return Ok(PosixTz { std: std, dst: None });
+        }
+
+        let dst_abbr = try!(self.parse_name());
+        let dst_utcoff = match self.peek() {
+            Some(b',') | None => std_utcoff + 3600,
+            Some(..) => -try!(self.parse_offset()),
+        };
+        let dst = LocalTimeType { utcoff: dst_utcoff, isdst: true, abbr: dst_abbr };
+
+        let (start, start_time, end, end_time) = if self.eat(b',') {
+            let start = try!(self.parse_date());
+            let start_time = if self.eat(b'/') { try!(self.parse_time()) } else { DEFAULT_TIME };
+            if !self.eat(b',') {
+                return Err(InvalidTzString);
+            }
+            let end = try!(self.parse_date());
+            let end_time = if self.eat(b'/') { try!(self.parse_time()) } else { DEFAULT_TIME };
+            (start, start_time, end, end_time)
+        } else {
+            let (start, end) = DEFAULT_RULE;
+            (start, DEFAULT_TIME, end, DEFAULT_TIME)
+        };
+
+        Ok(PosixTz {
+            std: std,
+            dst: Some(DstRule {
+                dst: dst,
+                start: start,
+                start_time: start_time,
+                end: end,
+                end_time: end_time,
+            }),
+        })
+    }
+
+    /// Parses an abbreviation: either at least three letters, or at least
+    /// three letters, digits and signs enclosed in angle brackets.
+    fn parse_name(&mut self) -> Result<String, ZoneError> {
+        let quoted = self.eat(b'<');
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(b'a' ... b'z') | Some(b'A' ... b'Z') => (),
+                Some(b'0' ... b'9') | Some(b'+') | Some(b'-') if quoted => (),
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        let end = self.pos;
+        if (quoted && !self.eat(b'>')) || end - start < 3 {
+            return Err(InvalidTzString);
+        }
+        // Only ASCII has been accepted, so this is valid UTF-8.
+        Ok(String::from_utf8(self.s.slice(start, end).to_vec()).unwrap())
+    }
+
+    /// Parses an unsigned decimal number of at most `max` value.
+    fn parse_num(&mut self, max: i32) -> Result<i32, ZoneError> {
+        let start = self.pos;
+        let mut value = 0;
+        loop {
+            match self.peek() {
+                Some(b @ b'0' ... b'9') => {
+                    value = value * 10 + (b - b'0') as i32;
+                    if value > max {
+                        return Err(InvalidTzString);
+                    }
+                }
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        if self.pos == start { Err(InvalidTzString) } else { Ok(value) }
+    }
+
+    /// Parses `[+-]hh[:mm[:ss]]` with at most `max_hours` hours, returning
+    /// seconds.
+    fn parse_hms(&mut self, max_hours: i32) -> Result<i32, ZoneError> {
+        let sign = if self.eat(b'-') { -1 } else { self.eat(b'+'); 1 };
+        let mut secs = try!(self.parse_num(max_hours)) * 3600;
+        if self.eat(b':') {
+            secs += try!(self.parse_num(59)) * 60;
+            if self.eat(b':') {
+                secs += try!(self.parse_num(59));
+            }
+        }
+        Ok(sign * secs)
+    }
+
+    /// Parses a UTC offset, in seconds west of UTC.
+    fn parse_offset(&mut self) -> Result<i32, ZoneError> {
+        self.parse_hms(24)
+    }
+
+    /// Parses the time of day of a transition, in seconds.
+    fn parse_time(&mut self) -> Result<i32, ZoneError> {
+        self.parse_hms(167)
+    }
+
+    fn parse_date(&mut self) -> Result<RuleDate, ZoneError> {
+        if self.eat(b'J') {
+            let n = try!(self.parse_num(365));
+            if n < 1 {
+                return Err(InvalidTzString);
+            }
+            Ok(RuleDate::Julian1(n as i64))
+        } else if self.eat(b'M') {
+            let month = try!(self.parse_num(12));
+            if month < 1 || !self.eat(b'.') {
+                return Err(InvalidTzString);
+            }
+            let week = try!(self.parse_num(5));
+            if week < 1 || !self.eat(b'.') {
+                return Err(InvalidTzString);
+            }
+            let wday = try!(self.parse_num(6));
+            Ok(RuleDate::MonthWeekDay(month as i64, week as i64, wday as i64))
+        } else {
+            Ok(RuleDate::Julian0(try!(self.parse_num(365)) as i64))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::i64;
+    use super::PosixTz;
+    use zoneinfo::{LocalTimeType, TimeZone};
+    use zoneinfo::ZoneError::InvalidTzString;
+
+    fn utcoff_at(tz: &PosixTz, sec: i64) -> i32 {
+        tz.local_time_type(sec).utcoff
+    }
+
+    #[test]
+    fn test_parse() {
+        let tz = PosixTz::parse("JST-9").unwrap();
+        assert_eq!(tz.local_time_type(0),
+                   &LocalTimeType { utcoff: 32400, isdst: false, abbr: "JST".to_string() });
+        assert!(tz.dst.is_none());
+
+        let tz = PosixTz::parse("<+0530>-5:30").unwrap();
+        assert_eq!(tz.local_time_type(0).utcoff, 19800);
+        assert_eq!(tz.local_time_type(0).abbr, "+0530".to_string());
+
+        let tz = PosixTz::parse("<-02>2<-01>,M3.5.0/-1,M10.5.0/0").unwrap();
+        assert_eq!(tz.std.utcoff, -7200);
+        let rule = tz.dst.unwrap();
+        assert_eq!(rule.dst.utcoff, -3600);
+        assert_eq!(rule.dst.abbr, "-01".to_string());
+        assert_eq!(rule.start_time, -3600);
+        assert_eq!(rule.end_time, 0);
+
+        let tz = PosixTz::parse("IST-2IDT,M3.4.4/26,M10.5.0").unwrap();
+        assert_eq!(tz.dst.unwrap().start_time, 26 * 3600);
+
+        let tz = PosixTz::parse("NZST-12:00:00NZDT-13:00:00,M9.5.0,M4.1.0/3").unwrap();
+        assert_eq!(tz.std.utcoff, 43200);
+        assert_eq!(tz.dst.unwrap().dst.utcoff, 46800);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let invalid = ["", "CE-1", "CET", "CET-1:", "CET-25", "<CE>-1", "<CET-1",
+                       "CET-1CEST,M3.5.0", "CET-1CEST,M13.1.0,M10.5.0",
+                       "CET-1CEST,M3.6.0,M10.5.0", "CET-1CEST,M3.5.7,M10.5.0",
+                       "CET-1CEST,M3.5,M10.5.0", "CET-1CEST,J0,J300",
+                       "CET-1CEST,366,300", "CET-1CEST,M3.5.0/168,M10.5.0",
+                       "CET-1CEST,M3.5.0,M10.5.0/3 ", "CET-1 "];
+        for &s in invalid.iter() {
+            assert_eq!(PosixTz::parse(s), Err(InvalidTzString));
+        }
+    }
+
+    #[test]
+    fn test_northern_rules() {
+        let tz = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        // 2014-03-30T01:00:00Z and 2014-10-26T01:00:00Z
+        assert_eq!(utcoff_at(&tz, 1396141199), 3600);
+        assert_eq!(utcoff_at(&tz, 1396141200), 7200);
+        assert_eq!(utcoff_at(&tz, 1414285199), 7200);
+        assert_eq!(utcoff_at(&tz, 1414285200), 3600);
+        assert!(tz.local_time_type(1396141200).isdst);
+        assert_eq!(tz.local_time_type(1396141200).abbr, "CEST".to_string());
+
+        // The DST offset defaults to one hour ahead and the rules to those
+        // of the United States: 2015-03-08T07:00:00Z and 2015-11-01T06:00:00Z.
+        let tz = PosixTz::parse("EST5EDT").unwrap();
+        assert_eq!(utcoff_at(&tz, 1425797999), -18000);
+        assert_eq!(utcoff_at(&tz, 1425798000), -14400);
+        assert_eq!(utcoff_at(&tz, 1446357599), -14400);
+        assert_eq!(utcoff_at(&tz, 1446357600), -18000);
+    }
+
+    #[test]
+    fn test_southern_rules() {
+        // 2015-04-05T03:00:00+11:00 and 2015-10-04T02:00:00+10:00
+        let tz = PosixTz::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(utcoff_at(&tz, 1420070400), 39600);
+        assert_eq!(utcoff_at(&tz, 1428163199), 39600);
+        assert_eq!(utcoff_at(&tz, 1428163200), 36000);
+        assert_eq!(utcoff_at(&tz, 1443887999), 36000);
+        assert_eq!(utcoff_at(&tz, 1443888000), 39600);
+        assert_eq!(utcoff_at(&tz, 1451606399), 39600);
+    }
+
+    #[test]
+    fn test_julian_days() {
+        // J60 is always March 1; day 59 is February 29 in leap years.
+        let tz = PosixTz::parse("AAA0BBB,J60/0,300/0").unwrap();
+        // 2015-03-01T00:00:00Z, 2016-02-29T00:00:00Z, 2016-03-01T00:00:00Z
+        assert_eq!(utcoff_at(&tz, 1425167999), 0);
+        assert_eq!(utcoff_at(&tz, 1425168000), 3600);
+        assert_eq!(utcoff_at(&tz, 1456704000), 0);
+        assert_eq!(utcoff_at(&tz, 1456790400), 3600);
+
+        let tz = PosixTz::parse("AAA0BBB,59/0,300/0").unwrap();
+        assert_eq!(utcoff_at(&tz, 1425168000), 3600);
+        assert_eq!(utcoff_at(&tz, 1456703999), 0);
+        assert_eq!(utcoff_at(&tz, 1456704000), 3600);
+
+        // Daylight saving time all year round.
+        let tz = PosixTz::parse("EST5EDT,0/0,J365/25").unwrap();
+        assert_eq!(utcoff_at(&tz, 1420088400), -14400);
+        assert_eq!(utcoff_at(&tz, 1435708800), -14400);
+        assert_eq!(utcoff_at(&tz, 1451624399), -14400);
+    }
+
+    #[test]
+    fn test_next_transition() {
+        let tz = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        // 2014-01-01T00:00:00Z, 2014-03-30T01:00:00Z and 2014-10-26T01:00:00Z
+        assert_eq!(tz.next_transition(1388534400), Some(1396141200));
+        assert_eq!(tz.next_transition(1396141200), Some(1396141200));
+        assert_eq!(tz.next_transition(1396141201), Some(1414285200));
+
+        let tz = PosixTz::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(tz.next_transition(1420070400), Some(1428163200));
+
+        // Years near the ends of `i64` are clamped to the supported range.
+        let tz = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        assert_eq!(tz.next_transition(i64::MAX), None);
+        assert!(tz.next_transition(i64::MIN).is_some());
+        assert_eq!(tz.local_time_type(i64::MAX).utcoff, 3600);
+        assert_eq!(tz.local_time_type(i64::MIN).utcoff, 3600);
+
+        assert_eq!(PosixTz::parse("JST-9").unwrap().next_transition(0), None);
+        assert_eq!(PosixTz::parse("EST5EDT,0/0,J365/25").unwrap().next_transition(0), None);
+    }
+
+    #[test]
+    fn test_matches_zoneinfo() {
+        let zones: [(&[u8], &str); 3] = [
+            (include_bytes!("../tests/zoneinfo/Europe/Berlin"), "CET-1CEST,M3.5.0,M10.5.0/3"),
+            (include_bytes!("../tests/zoneinfo/America/Los_Angeles"), "PST8PDT,M3.2.0,M11.1.0"),
+            (include_bytes!("../tests/zoneinfo/Asia/Jerusalem"), "IST-2IDT,M3.4.4/26,M10.5.0"),
+        ];
+        for &(data, s) in zones.iter() {
+            let zone = TimeZone::from_bytes(data).unwrap();
+            let tz = PosixTz::parse(s).unwrap();
+            // Every quarter of an hour from 2013 to 2035.
+            let mut sec = 1356998400;
+            while sec < 2051222400 {
+                assert_eq!(zone.local_time_type(sec), tz.local_time_type(sec));
+                sec += 900;
+            }
+        }
+    }
+}
@@ -0,0 +1,443 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Truncating and rounding instants to units of wall-clock time.
+
+use std::time::Duration;
+
+use super::{Timespec, Tm, NSEC_PER_SEC};
+use calendar::{self, SECS_PER_DAY, mod_floor};
+use month;
+use zoneinfo::{TimeZone, LocalResult, Disambiguate};
+
+/// A unit of wall-clock time that `Timespec::trunc_to`,
+/// `Timespec::round_to` and `Tm::start_of` align instants to.
+///
+/// Boundaries between units are found on the wall clock of a time zone, so a
+/// day runs from one local midnight to the next, whatever its length. When
+/// the clock is set back and shows a boundary twice, both instants are
+/// boundaries. When it jumps over a boundary, as when daylight saving time
+/// starts at midnight, the instant of the jump is the boundary instead.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum TimeUnit {
+    Minute,
+    Hour,
+    Day,
+    /// A week starting on Monday, as in ISO 8601 and as numbered by `%W`.
+    Week,
+    /// A week starting on Sunday, as numbered by `%U`.
+    SundayWeek,
+    Month,
+    /// Three months starting in January, April, July or October.
+    Quarter,
+    Year,
+    /// Three months of a fiscal year starting in the given month.
+    FiscalQuarter(month::Month),
+    /// A fiscal year starting in the given month, such as April 1 to March 31.
+    FiscalYear(month::Month),
+    /// A multiple of a positive duration of at most one day, counted on the
+    /// wall clock from each local midnight. Fifteen minutes gives boundaries
+    /// at :00, :15, :30 and :45 of every local hour.
+    Multiple(Duration),
+}
+
+/// Returns the start of the `unit` of wall-clock time in `zone` that
+/// contains `clock`.
+pub fn trunc(clock: Timespec, unit: TimeUnit, zone: &TimeZone) -> Timespec {
+    let local = floor_local(&zone.at(clock), unit);
+    match zone.local_to_timespec(&local) {
+        LocalResult::Single(start) => start,
+        LocalResult::Ambiguous(earlier, later) => if later <= clock { later } else { earlier },
+        LocalResult::None => gap_end(&local, zone),
+    }
+}
+
+/// Returns the boundary of `unit` in `zone` nearest to `clock`, rounding
+/// halfway cases up.
+pub fn round(clock: Timespec, unit: TimeUnit, zone: &TimeZone) -> Timespec {
+    let start = trunc(clock, unit, zone);
+    let end = next_boundary(start, unit, zone);
+    if clock - start < end - clock { start } else { end }
+}
+
+/// Returns the first instant of the `unit` of wall-clock time in `zone` that
+/// contains `clock`, and the first instant after the unit.
+///
+/// Unlike `trunc`, this treats the unit as a period of the calendar: a clock
+/// that is set back without leaving the unit does not start it again, so a
+/// day whose midnight is shown twice starts at the first midnight. A clock
+/// that is set back to an earlier unit ends the unit then.
+pub fn period(clock: Timespec, unit: TimeUnit, zone: &TimeZone) -> (Timespec, Timespec) {
+    let local = floor_local(&zone.at(clock), unit);
+    let in_unit = |t: Timespec| same_wall_clock(&floor_local(&zone.at(t), unit), &local);
+    let start = match zone.local_to_timespec(&local) {
+        LocalResult::Single(start) => start,
+        LocalResult::Ambiguous(earlier, later) => {
+            if later <= clock && !in_unit(later - Duration::nanoseconds(1)) {
+                later
+            } else {
+                earlier
+            }
+        }
+        LocalResult::None => gap_end(&local, zone),
+    };
+
+    let next = add_unit(local, unit);
+    let end = match zone.local_to_timespec(&next) {
+        LocalResult::Single(end) => end,
+        LocalResult::Ambiguous(earlier, later) => if earlier > start { earlier } else { later },
+        LocalResult::None => gap_end(&next, zone),
+    };
+    match zone.transitions(start, end).find(|t| t.instant > start && !in_unit(t.instant)) {
+        Some(transition) => (start, transition.instant),
+        None => (start, end),
+    }
+}
+
+/// Returns the first boundary of `unit` in `zone` after the boundary `start`.
+pub fn next_boundary(start: Timespec, unit: TimeUnit, zone: &TimeZone) -> Timespec {
+    let local = floor_local(&zone.at(start), unit);
+    // A clock that is set back shows the same boundary again.
+    if let LocalResult::Ambiguous(_, later) = zone.local_to_timespec(&local) {
+        if later > start {
+            return later;
+        }
+    }
+
+    let next = add_unit(local, unit);
+    match zone.local_to_timespec(&next) {
+        LocalResult::Single(end) | LocalResult::Ambiguous(end, _) => end,
+        LocalResult::None => gap_end(&next, zone),
+    }
+}
+
+/// Returns the wall-clock time of the start of the `unit` containing `tm`.
+/// The result may have out-of-range fields, such as a `tm_mday` below 1.
+fn floor_local(tm: &Tm, unit: TimeUnit) -> Tm {
+    let mut tm = *tm;
+    if let TimeUnit::Multiple(step) = unit {
+        let nanos = nanos_of_day(&tm);
+        set_nanos_of_day(&mut tm, nanos - nanos % step_nanos(step));
+        return tm;
+    }
+
+    tm.tm_nsec = 0;
+    tm.tm_sec = 0;
+    if unit == TimeUnit::Minute {
+        return tm;
+    }
+    tm.tm_min = 0;
+    if unit == TimeUnit::Hour {
+        return tm;
+    }
+    tm.tm_hour = 0;
+    match unit {
+        TimeUnit::Week => tm.tm_mday -= (tm.tm_wday + 6) % 7,
+        TimeUnit::SundayWeek => tm.tm_mday -= tm.tm_wday,
+        TimeUnit::Month => tm.tm_mday = 1,
+        TimeUnit::Quarter => {
+            tm.tm_mday = 1;
+            tm.tm_mon -= tm.tm_mon % 3;
+        }
+        TimeUnit::Year => {
+            tm.tm_mday = 1;
+            tm.tm_mon = 0;
+        }
+        TimeUnit::FiscalQuarter(start) => {
+            tm.tm_mday = 1;
+            tm.tm_mon -= mod_floor(tm.tm_mon as i64 - start as i64, 3) as i32;
+        }
+        TimeUnit::FiscalYear(start) => {
+            tm.tm_mday = 1;
+            tm.tm_mon -= mod_floor(tm.tm_mon as i64 - start as i64, 12) as i32;
+        }
+        _ => (),
+    }
+    tm
+}
+
+/// Moves the wall-clock time `tm`, which starts a `unit`, to the start of
+/// the next one.
+fn add_unit(mut tm: Tm, unit: TimeUnit) -> Tm {
+    match unit {
+        TimeUnit::Minute => tm.tm_min += 1,
+        TimeUnit::Hour => tm.tm_hour += 1,
+        TimeUnit::Day => tm.tm_mday += 1,
+        TimeUnit::Week | TimeUnit::SundayWeek => tm.tm_mday += 7,
+        TimeUnit::Month => tm.tm_mon += 1,
+        TimeUnit::Quarter | TimeUnit::FiscalQuarter(_) => tm.tm_mon += 3,
+        TimeUnit::Year => tm.tm_year += 1,
+        TimeUnit::FiscalYear(_) => tm.tm_mon += 12,
+        TimeUnit::Multiple(step) => {
+            let nanos = nanos_of_day(&tm) + step_nanos(step);
+            if nanos >= SECS_PER_DAY * NSEC_PER_SEC as i64 {
+                tm.tm_mday += 1;
+                set_nanos_of_day(&mut tm, 0);
+            } else {
+                set_nanos_of_day(&mut tm, nanos);
+            }
+        }
+    }
+    tm
+}
+
+/// Returns the instant at which the wall clock of `zone` jumped over the
+/// time in `local`.
+fn gap_end(local: &Tm, zone: &TimeZone) -> Timespec {
+    let shifted = zone.local_to_timespec_with(local, Disambiguate::ShiftForward).unwrap();
+    let from = Timespec::new(shifted.sec - SECS_PER_DAY, 0);
+    zone.transitions(from, shifted + Duration::nanoseconds(1))
+        .last()
+        .map_or(shifted, |transition| transition.instant)
+}
+
+/// Returns whether `a` and `b` show the same wall-clock time, whatever their
+/// offsets.
+fn same_wall_clock(a: &Tm, b: &Tm) -> bool {
+    calendar::local_seconds(a) == calendar::local_seconds(b) && a.tm_nsec == b.tm_nsec
+}
+
+fn step_nanos(step: Duration) -> i64 {
+    assert!(step > Duration::zero() && step <= Duration::days(1),
+            "`TimeUnit::Multiple` needs a positive duration of at most one day");
+    step.num_nanoseconds().unwrap()
+}
+
+fn nanos_of_day(tm: &Tm) -> i64 {
+    let secs = tm.tm_hour as i64 * 3600 + tm.tm_min as i64 * 60 + tm.tm_sec as i64;
+    secs * NSEC_PER_SEC as i64 + tm.tm_nsec as i64
+}
+
+fn set_nanos_of_day(tm: &mut Tm, nanos: i64) {
+    let secs = nanos / NSEC_PER_SEC as i64;
+    tm.tm_hour = (secs / 3600) as i32;
+    tm.tm_min = (secs / 60 % 60) as i32;
+    tm.tm_sec = (secs % 60) as i32;
+    tm.tm_nsec = (nanos % NSEC_PER_SEC as i64) as i32;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::TimeUnit::{Minute, Hour, Day, Week, SundayWeek, Month, Quarter, Year,
+                          FiscalQuarter, FiscalYear, Multiple};
+    use month::Month::{January, April, October};
+    use {Timespec, Tm, TimeZone, at_in, strptime};
+
+    fn los_angeles() -> TimeZone {
+        TimeZone::from_bytes(include_bytes!("../tests/zoneinfo/America/Los_Angeles")).unwrap()
+    }
+
+    fn at(sec: i64) -> Timespec {
+        Timespec::new(sec, 0)
+    }
+
+    #[test]
+    fn test_trunc() {
+        let zone = los_angeles();
+        // 2009-02-13 15:31:30.000054321 PST, a Friday.
+        let time = Timespec::new(1234567890, 54321);
+
+        assert_eq!(time.trunc_to(Minute, &zone), at(1234567860));
+        assert_eq!(time.trunc_to(Hour, &zone), at(1234566000));
+        assert_eq!(time.trunc_to(Day, &zone), at(1234512000));
+        assert_eq!(time.trunc_to(Week, &zone), at(1234166400));
+        assert_eq!(time.trunc_to(Month, &zone), at(1233475200));
+        assert_eq!(time.trunc_to(Multiple(Duration::minutes(15)), &zone), at(1234567800));
+        assert_eq!(time.trunc_to(Multiple(Duration::milliseconds(100)), &zone), at(1234567890));
+        assert_eq!(time.trunc_to(Day, &TimeZone::utc()), at(1234483200));
+        assert_eq!(at(1234512000).trunc_to(Day, &zone), at(1234512000));
+
+        let tm = at_in(time, &zone).trunc_to(Day, &zone);
+        assert_eq!((tm.tm_mday, tm.tm_hour, tm.tm_min, tm.tm_nsec), (13, 0, 0, 0));
+        assert_eq!(tm.tm_utcoff, -28800);
+
+        // Only the instant is kept; the offset is that of the zone.
+        let india = at_in(time, &TimeZone::from_tz_string("IST-5:30").unwrap());
+        assert_eq!(india.tm_mday, 14);
+        let tm = india.trunc_to(Day, &TimeZone::utc());
+        assert_eq!((tm.tm_mday, tm.tm_hour, tm.tm_utcoff), (13, 0, 0));
+    }
+
+    #[test]
+    fn test_round() {
+        let zone = los_angeles();
+        let time = Timespec::new(1234567890, 54321);
+
+        assert_eq!(time.round_to(Minute, &zone), at(1234567920));
+        assert_eq!(time.round_to(Hour, &zone), at(1234569600));
+        assert_eq!(time.round_to(Day, &zone), at(1234598400));
+        assert_eq!(time.round_to(Multiple(Duration::minutes(15)), &zone), at(1234567800));
+        assert_eq!(Timespec::new(0, 150_000_000).round_to(Multiple(Duration::milliseconds(100)),
+                                                          &zone),
+                   Timespec::new(0, 200_000_000));
+        // 23:59 rounds up to the next local midnight.
+        assert_eq!(at(1234598340).round_to(Multiple(Duration::hours(7)), &zone),
+                   at(1234598400));
+
+        let tm = at_in(time, &zone).round_to(Hour, &zone);
+        assert_eq!((tm.tm_mday, tm.tm_hour, tm.tm_min), (13, 16, 0));
+    }
+
+    #[test]
+    fn test_repeated_hour() {
+        let zone = los_angeles();
+        // On 2015-11-01 the clock went from 01:59:59 PDT back to 01:00 PST.
+        let (pdt, pst) = (1446364800, 1446368400);
+
+        assert_eq!(at(pdt + 1800).trunc_to(Hour, &zone), at(pdt));
+        assert_eq!(at(pst + 1800).trunc_to(Hour, &zone), at(pst));
+        assert_eq!(at(pdt + 1200).round_to(Hour, &zone), at(pdt));
+        assert_eq!(at(pdt + 2700).round_to(Hour, &zone), at(pst));
+        assert_eq!(at(pst + 2700).round_to(Hour, &zone), at(pst + 3600));
+        assert_eq!(at(pst + 1800).trunc_to(Day, &zone), at(1446361200));
+
+        // The day is 25 hours long, so noon is past its middle.
+        assert_eq!(at(1446408000).round_to(Day, &zone), at(1446451200));
+    }
+
+    #[test]
+    fn test_skipped_boundary() {
+        let zone = los_angeles();
+        // On 2015-03-08 the clock went from 01:59:59 PST to 03:00 PDT.
+        let jump = 1425808800;
+
+        assert_eq!(at(jump + 1800).trunc_to(Hour, &zone), at(jump));
+        assert_eq!(at(jump + 1800).trunc_to(Multiple(Duration::hours(2)), &zone), at(jump));
+        assert_eq!(at(jump - 1).round_to(Hour, &zone), at(jump));
+        assert_eq!(at(jump + 1800).trunc_to(Day, &zone), at(1425801600));
+
+        // Daylight saving time that starts at midnight, as Brazil's used to:
+        // 2015-10-18 started at 01:00 -02.
+        let zone = TimeZone::from_tz_string("<-03>3<-02>,M10.3.0/0,M2.3.0/0").unwrap();
+        let midnight = 1445137200;
+        assert_eq!(at(midnight + 9 * 3600).trunc_to(Day, &zone), at(midnight));
+        assert_eq!(at(midnight - 600).round_to(Day, &zone), at(midnight));
+        assert_eq!(at(midnight - 600).trunc_to(Day, &zone), at(midnight - 86400));
+    }
+
+    #[test]
+    fn test_start_end() {
+        // 2009-02-13 15:31:30.000054321 PST, a Friday.
+        let pst = TimeZone::from_tz_string("PST8").unwrap();
+        let tm = at_in(Timespec::new(1234567890, 54321), &pst);
+        let fields = |tm: Tm| (tm.tm_year, tm.tm_mon, tm.tm_mday, tm.tm_hour, tm.tm_min,
+                               tm.tm_sec, tm.tm_nsec);
+        let last = (23, 59, 59, 999_999_999);
+
+        assert_eq!(tm.start_of(Day, &pst).to_timespec(), at(1234512000));
+        assert_eq!(fields(tm.start_of(Day, &pst)), (109, 1, 13, 0, 0, 0, 0));
+        assert_eq!(fields(tm.end_of(Day, &pst)), (109, 1, 13, last.0, last.1, last.2, last.3));
+        assert_eq!(fields(tm.start_of(Hour, &pst)), (109, 1, 13, 15, 0, 0, 0));
+        assert_eq!(fields(tm.start_of(Week, &pst)), (109, 1, 9, 0, 0, 0, 0));
+        assert_eq!(fields(tm.end_of(Week, &pst)), (109, 1, 15, last.0, last.1, last.2, last.3));
+        assert_eq!(fields(tm.start_of(SundayWeek, &pst)), (109, 1, 8, 0, 0, 0, 0));
+        assert_eq!(fields(tm.end_of(SundayWeek, &pst)),
+                   (109, 1, 14, last.0, last.1, last.2, last.3));
+        assert_eq!(fields(tm.start_of(Month, &pst)), (109, 1, 1, 0, 0, 0, 0));
+        assert_eq!(fields(tm.end_of(Month, &pst)), (109, 1, 28, last.0, last.1, last.2, last.3));
+        assert_eq!(fields(tm.start_of(Quarter, &pst)), (109, 0, 1, 0, 0, 0, 0));
+        assert_eq!(fields(tm.end_of(Quarter, &pst)), (109, 2, 31, last.0, last.1, last.2, last.3));
+        assert_eq!(fields(tm.start_of(Year, &pst)), (109, 0, 1, 0, 0, 0, 0));
+        assert_eq!(fields(tm.end_of(Year, &pst)), (109, 11, 31, last.0, last.1, last.2, last.3));
+        assert_eq!(tm.start_of(Year, &pst).to_timespec(), at(1230796800));
+
+        // Fiscal year 2008 started on 2008-04-01, and its fourth quarter on
+        // 2009-01-01.
+        assert_eq!((tm.fiscal_year(April), tm.fiscal_quarter(April)), (2008, 4));
+        assert_eq!(fields(tm.start_of(FiscalYear(April), &pst)), (108, 3, 1, 0, 0, 0, 0));
+        assert_eq!(fields(tm.end_of(FiscalYear(April), &pst)),
+                   (109, 2, 31, last.0, last.1, last.2, last.3));
+        assert_eq!(fields(tm.start_of(FiscalQuarter(April), &pst)), (109, 0, 1, 0, 0, 0, 0));
+        assert_eq!(fields(tm.end_of(FiscalQuarter(April), &pst)),
+                   (109, 2, 31, last.0, last.1, last.2, last.3));
+        assert_eq!((tm.fiscal_year(October), tm.fiscal_quarter(October)), (2008, 2));
+        assert_eq!(fields(tm.start_of(FiscalYear(October), &pst)), (108, 9, 1, 0, 0, 0, 0));
+        assert_eq!(fields(tm.start_of(FiscalQuarter(October), &pst)), (109, 0, 1, 0, 0, 0, 0));
+        assert_eq!(tm.start_of(FiscalYear(January), &pst), tm.start_of(Year, &pst));
+        assert_eq!(tm.quarter(), 1);
+
+        // The weeks match the ones `%W` and `%U` number.
+        for &(unit, spec) in [(Week, "%W"), (SundayWeek, "%U")].iter() {
+            let week = tm.strftime(spec).unwrap().to_string();
+            assert_eq!(tm.start_of(unit, &pst).strftime(spec).unwrap().to_string(), week);
+            assert_eq!(tm.end_of(unit, &pst).strftime(spec).unwrap().to_string(), week);
+        }
+        assert_eq!(tm.start_of(Week, &pst).tm_wday, 1);
+        assert_eq!(tm.start_of(SundayWeek, &pst).tm_wday, 0);
+        assert_eq!(tm.end_of(Year, &pst).tm_yday, 364);
+
+        // The units follow the wall clock of the zone, not that of the offset
+        // of `self`.
+        let india = TimeZone::from_tz_string("IST-5:30").unwrap();
+        let start = tm.start_of(Day, &india);
+        assert_eq!((start.tm_mday, start.tm_hour, start.tm_utcoff), (14, 0, 19800));
+
+        // A fixed zone keeps the offset of a parsed time.
+        let parsed = strptime("2009-02-14 05:01:30 +0530", "%Y-%m-%d %H:%M:%S %z").unwrap();
+        let zone = TimeZone::fixed(parsed.tm_utcoff);
+        assert_eq!(parsed.start_of(Day, &zone), start);
+        let end = parsed.end_of(Month, &zone);
+        assert_eq!(fields(end), (109, 1, 28, last.0, last.1, last.2, last.3));
+        assert_eq!(end.tm_utcoff, 19800);
+    }
+
+    #[test]
+    fn test_start_end_in() {
+        let zone = los_angeles();
+        // 2015-11-15 12:00 PST; the month started in daylight saving time.
+        let tm = at_in(at(1447617600), &zone);
+        let start = tm.start_of(Month, &zone);
+        assert_eq!((start.tm_mday, start.tm_hour, start.tm_utcoff), (1, 0, -25200));
+        assert_eq!(start.to_timespec(), at(1446361200));
+        let end = tm.end_of(Year, &zone);
+        assert_eq!((end.tm_mon, end.tm_mday, end.tm_hour, end.tm_utcoff), (11, 31, 23, -28800));
+        assert_eq!(end.to_timespec(), Timespec::new(1451635199, 999_999_999));
+
+        // On 2015-10-18 the day started at 01:00 -02, an hour later than a
+        // fixed offset would put it.
+        let zone = TimeZone::from_tz_string("<-03>3<-02>,M10.3.0/0,M2.3.0/0").unwrap();
+        let tm = at_in(at(1445137200 + 9 * 3600), &zone);
+        let start = tm.start_of(Day, &zone);
+        assert_eq!((start.tm_mday, start.tm_hour, start.tm_utcoff), (18, 1, -7200));
+        assert_eq!(start.to_timespec(), at(1445137200));
+        let end = at_in(at(1445137200 - 12 * 3600), &zone).end_of(Day, &zone);
+        assert_eq!((end.tm_mday, end.tm_hour, end.tm_utcoff), (17, 23, -10800));
+        assert_eq!(end.to_timespec(), Timespec::new(1445137199, 999_999_999));
+
+        // On 2016-02-21 the clock went back from 01:00 -02 to 00:00 -03, so
+        // midnight came twice. The day starts at the first, where `trunc_to`
+        // counts both.
+        let zone = TimeZone::from_tz_string("<-03>3<-02>,M10.3.0/0,M2.3.0/1").unwrap();
+        let (first, second) = (at(1456020000), at(1456023600));
+        let tm = at_in(second + Duration::minutes(30), &zone);
+        assert_eq!(tm.start_of(Day, &zone).to_timespec(), first);
+        assert_eq!(tm.start_of(Hour, &zone).to_timespec(), first);
+        assert_eq!(tm.trunc_to(Day, &zone).to_timespec(), second);
+        let end = at_in(first - Duration::hours(12), &zone).end_of(Day, &zone);
+        assert_eq!(end.to_timespec(), first - Duration::nanoseconds(1));
+
+        // A minute the clock leaves and shows again is two periods.
+        let zone = los_angeles();
+        // 2015-11-01 01:59:30 PDT and PST.
+        let pdt = at_in(at(1446368370), &zone);
+        assert_eq!(pdt.start_of(Minute, &zone).to_timespec(), at(1446368340));
+        assert_eq!(pdt.end_of(Minute, &zone).to_timespec(), Timespec::new(1446368399, 999_999_999));
+        let pst = at_in(at(1446371970), &zone);
+        assert_eq!(pst.start_of(Minute, &zone).to_timespec(), at(1446371940));
+        assert_eq!(pst.start_of(Day, &zone).to_timespec(), at(1446361200));
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_multiple_too_long() {
+        at(0).trunc_to(Multiple(Duration::days(2)), &TimeZone::utc());
+    }
+}
@@ -105,8 +105,10 @@ static void tm_to_rust_tm(struct tm* in_tm,
 #if defined(__WIN32__)
 #define TZSET() _tzset()
 #if defined(_MSC_VER) && (_MSC_VER >= 1400)
-#define GMTIME(clock, result) gmtime_s((result), (clock))
-#define LOCALTIME(clock, result) localtime_s((result), (clock))
+#define GMTIME(clock, result) \
+    (gmtime_s((result), (clock)) == 0 ? (result) : NULL)
+#define LOCALTIME(clock, result) \
+    (localtime_s((result), (clock)) == 0 ? (result) : NULL)
 #define TIMEGM(result) _mkgmtime64(result)
 #else
 static struct tm* GMTIME(const time_t *clock, struct tm *result) {
@@ -142,20 +144,31 @@ rust_time_tzset() {
     TZSET();
 }
 
-void
+// Returns 0 if the time does not fit in a time_t or cannot be converted, such
+// as a time before 1970 with gmtime_s.
+int32_t
 rust_time_gmtime(int64_t sec, int32_t nsec, rust_time_tm *timeptr) {
     struct tm tm;
     time_t s = sec;
-    GMTIME(&s, &tm);
+    if (s != sec || GMTIME(&s, &tm) == NULL) {
+        return 0;
+    }
 
     tm_to_rust_tm(&tm, timeptr, 0, nsec);
+    return 1;
 }
 
-void
-rust_time_localtime(int64_t sec, int32_t nsec, rust_time_tm *timeptr) {
+// Returns 0 if the time does not fit in a time_t or a struct tm. Unless abbr
+// is NULL, the zone abbreviation printed by strftime's %Z is written to it,
+// or an empty string if the abbreviation and its NUL do not fit in len bytes.
+int32_t
+rust_time_localtime(int64_t sec, int32_t nsec, rust_time_tm *timeptr,
+                    char *abbr, size_t len) {
     struct tm tm;
     time_t s = sec;
-    LOCALTIME(&s, &tm);
+    if (s != sec || LOCALTIME(&s, &tm) == NULL) {
+        return 0;
+    }
 
 #if defined(__WIN32__)
     int32_t utcoff = -timezone;
@@ -166,6 +179,10 @@ rust_time_localtime(int64_t sec, int32_t nsec, rust_time_tm *timeptr) {
 #endif
 
     tm_to_rust_tm(&tm, timeptr, utcoff, nsec);
+    if (abbr != NULL && len > 0 && strftime(abbr, len, "%Z", &tm) == 0) {
+        abbr[0] = '\0';
+    }
+    return 1;
 }
 
 int64_t
@@ -174,10 +191,3 @@ rust_time_timegm(rust_time_tm* timeptr) {
     rust_time_tm_to_tm(timeptr, &t);
     return TIMEGM(&t);
 }
-
-int64_t
-rust_time_mktime(rust_time_tm* timeptr) {
-    struct tm t;
-    rust_time_tm_to_tm(timeptr, &t);
-    return mktime(&t);
-}
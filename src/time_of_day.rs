@@ -0,0 +1,310 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Times of day without a date.
+
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::time::Duration;
+
+use super::{Tm, TmFmt, ParseError, NSEC_PER_SEC, empty_tm, strptime};
+use super::Fmt::FmtStr;
+use super::ParseError::{InvalidHour, InvalidMinute, InvalidSecond, InvalidNanosecond,
+                        InvalidFormatSpecifier, MissingFormatConverter};
+use calendar::{self, SECS_PER_DAY};
+
+/// A time of day, from 00:00:00 to 23:59:59.999999999, with nanosecond
+/// precision.
+///
+/// Leap seconds cannot be represented. Times are ordered from midnight.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Show)]
+pub struct Time {
+    hour: i32,
+    minute: i32,
+    second: i32,
+    nanosecond: i32,
+}
+
+impl Time {
+    /// Returns the time `hour`:`minute`:`second`, or an error naming the
+    /// first field that is out of range.
+    pub fn from_hms(hour: i32, minute: i32, second: i32) -> Result<Time, ParseError> {
+        Time::from_hms_nano(hour, minute, second, 0)
+    }
+
+    /// Returns the time `hour`:`minute`:`second` plus `nanosecond`
+    /// nanoseconds, or an error naming the first field that is out of range.
+    pub fn from_hms_nano(hour: i32, minute: i32, second: i32, nanosecond: i32)
+                         -> Result<Time, ParseError> {
+        if hour < 0 || hour > 23 {
+            return Err(InvalidHour);
+        }
+        if minute < 0 || minute > 59 {
+            return Err(InvalidMinute);
+        }
+        if second < 0 || second > 59 {
+            return Err(InvalidSecond);
+        }
+        if nanosecond < 0 || nanosecond >= NSEC_PER_SEC {
+            return Err(InvalidNanosecond);
+        }
+        Ok(Time { hour: hour, minute: minute, second: second, nanosecond: nanosecond })
+    }
+
+    /// Returns the time of day held by the `tm_hour`, `tm_min`, `tm_sec` and
+    /// `tm_nsec` fields of `tm`. The other fields are ignored.
+    pub fn from_tm(tm: &Tm) -> Result<Time, ParseError> {
+        Time::from_hms_nano(tm.tm_hour, tm.tm_min, tm.tm_sec, tm.tm_nsec)
+    }
+
+    /// Returns midnight, 00:00:00.
+    pub fn midnight() -> Time {
+        Time { hour: 0, minute: 0, second: 0, nanosecond: 0 }
+    }
+
+    /// Returns the hour, in `[0, 23]`.
+    pub fn hour(&self) -> i32 {
+        self.hour
+    }
+
+    /// Returns the minute, in `[0, 59]`.
+    pub fn minute(&self) -> i32 {
+        self.minute
+    }
+
+    /// Returns the second, in `[0, 59]`.
+    pub fn second(&self) -> i32 {
+        self.second
+    }
+
+    /// Returns the nanoseconds after the second, in `[0, 10^9 - 1]`.
+    pub fn nanosecond(&self) -> i32 {
+        self.nanosecond
+    }
+
+    /// Returns the number of whole seconds since midnight.
+    pub fn seconds_from_midnight(&self) -> i32 {
+        self.hour * 3600 + self.minute * 60 + self.second
+    }
+
+    /// Adds `rhs`, wrapping around midnight. Returns the new time and the
+    /// number of days that were wrapped, negative if `rhs` went backwards
+    /// past midnight.
+    pub fn overflowing_add(&self, rhs: Duration) -> (Time, i64) {
+        let d_sec = rhs.num_seconds();
+        // It is safe to unwrap the nanoseconds, because there cannot be
+        // more than one second left, which fits in i64 and in i32.
+        let d_nsec = (rhs - Duration::seconds(d_sec)).num_nanoseconds().unwrap() as i32;
+        let mut sec = self.seconds_from_midnight() as i64 + d_sec;
+        let mut nsec = self.nanosecond + d_nsec;
+        if nsec >= NSEC_PER_SEC {
+            nsec -= NSEC_PER_SEC;
+            sec += 1;
+        } else if nsec < 0 {
+            nsec += NSEC_PER_SEC;
+            sec -= 1;
+        }
+        let days = calendar::div_floor(sec, SECS_PER_DAY);
+        let sec = calendar::mod_floor(sec, SECS_PER_DAY) as i32;
+        let time = Time {
+            hour: sec / 3600,
+            minute: sec / 60 % 60,
+            second: sec % 60,
+            nanosecond: nsec,
+        };
+        (time, days)
+    }
+
+    /// Subtracts `rhs`, wrapping around midnight. Returns the new time and
+    /// the number of days that were wrapped, negative if `rhs` went
+    /// backwards past midnight.
+    pub fn overflowing_sub(&self, rhs: Duration) -> (Time, i64) {
+        self.overflowing_add(-rhs)
+    }
+
+    /// Adds `rhs`, wrapping around midnight.
+    pub fn wrapping_add(&self, rhs: Duration) -> Time {
+        self.overflowing_add(rhs).0
+    }
+
+    /// Subtracts `rhs`, wrapping around midnight.
+    pub fn wrapping_sub(&self, rhs: Duration) -> Time {
+        self.overflowing_sub(rhs).0
+    }
+
+    /// Formats the time according to the format string, which may only use
+    /// the time specifiers `%H`, `%M`, `%S`, `%f`, `%p`, `%I`, `%R`, `%T`
+    /// and `%%`.
+    pub fn strftime<'a>(&self, format: &'a str) -> Result<TimeFmt<'a>, ParseError> {
+        try!(validate_time_format(format));
+        let tm = Tm {
+            tm_sec: self.second,
+            tm_min: self.minute,
+            tm_hour: self.hour,
+            tm_mday: 1,
+            tm_nsec: self.nanosecond,
+            ..empty_tm()
+        };
+        Ok(TimeFmt { tm: tm, format: format })
+    }
+
+    /// Parses a time of day from the string according to the format string,
+    /// which may only use the specifiers accepted by `strftime`.
+    pub fn strptime(s: &str, format: &str) -> Result<Time, ParseError> {
+        try!(validate_time_format(format));
+        strptime(s, format).and_then(|tm| Time::from_tm(&tm))
+    }
+}
+
+fn validate_time_format(format: &str) -> Result<(), ParseError> {
+    let mut chars = format.chars();
+    loop {
+        match chars.next() {
+            Some('%') => match chars.next() {
+                Some('H') | Some('M') | Some('S') | Some('f') | Some('p') |
+                Some('I') | Some('R') | Some('T') | Some('%') => (),
+                Some(c) => return Err(InvalidFormatSpecifier(c)),
+                None => return Err(MissingFormatConverter),
+            },
+            Some(..) => (),
+            None => return Ok(()),
+        }
+    }
+}
+
+impl Add<Duration> for Time {
+    type Output = Time;
+
+    /// Wraps around midnight.
+    fn add(self, other: Duration) -> Time {
+        self.wrapping_add(other)
+    }
+}
+
+impl Sub<Duration> for Time {
+    type Output = Time;
+
+    /// Wraps around midnight.
+    fn sub(self, other: Duration) -> Time {
+        self.wrapping_sub(other)
+    }
+}
+
+impl fmt::String for Time {
+    /// Writes the time as "09:30:00", followed by the nanoseconds if there
+    /// are any, as in "09:30:00.250000000".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second));
+        if self.nanosecond != 0 {
+            try!(write!(f, ".{:09}", self.nanosecond));
+        }
+        Ok(())
+    }
+}
+
+/// A wrapper around a `Time` and format string that implements Show.
+pub struct TimeFmt<'a> {
+    tm: Tm,
+    format: &'a str,
+}
+
+impl<'a> fmt::String for TimeFmt<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::String::fmt(&TmFmt { tm: &self.tm, zone: None, format: FmtStr(self.format) }, f)
+    }
+}
+
+impl<'a> fmt::Show for TimeFmt<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::String::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::Time;
+    use ParseError::{InvalidHour, InvalidMinute, InvalidSecond, InvalidNanosecond,
+                     InvalidFormatSpecifier};
+    use {Timespec, at_utc};
+
+    #[test]
+    fn test_from_hms() {
+        let time = Time::from_hms_nano(9, 30, 15, 250).unwrap();
+        assert_eq!((time.hour(), time.minute(), time.second(), time.nanosecond()),
+                   (9, 30, 15, 250));
+        assert_eq!(Time::from_hms(0, 0, 0), Ok(Time::midnight()));
+        assert!(Time::from_hms(23, 59, 59).is_ok());
+
+        assert_eq!(Time::from_hms(24, 0, 0), Err(InvalidHour));
+        assert_eq!(Time::from_hms(-1, 0, 0), Err(InvalidHour));
+        assert_eq!(Time::from_hms(9, 60, 0), Err(InvalidMinute));
+        assert_eq!(Time::from_hms(9, 30, 60), Err(InvalidSecond));
+        assert_eq!(Time::from_hms_nano(9, 30, 0, 1_000_000_000), Err(InvalidNanosecond));
+
+        let tm = at_utc(Timespec::new(1234567890, 54321));
+        assert_eq!(Time::from_tm(&tm), Time::from_hms_nano(23, 31, 30, 54321));
+    }
+
+    #[test]
+    fn test_ord() {
+        let a = Time::from_hms(9, 30, 0).unwrap();
+        let b = Time::from_hms_nano(9, 30, 0, 1).unwrap();
+        let c = Time::from_hms(10, 0, 0).unwrap();
+        assert!(Time::midnight() < a);
+        assert!(a < b);
+        assert!(b < c);
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let time = Time::from_hms(23, 0, 0).unwrap();
+        assert_eq!(time.overflowing_add(Duration::minutes(30)),
+                   (Time::from_hms(23, 30, 0).unwrap(), 0));
+        assert_eq!(time.overflowing_add(Duration::hours(2)),
+                   (Time::from_hms(1, 0, 0).unwrap(), 1));
+        assert_eq!(time.overflowing_add(Duration::days(3) + Duration::nanoseconds(5)),
+                   (Time::from_hms_nano(23, 0, 0, 5).unwrap(), 3));
+        assert_eq!(time.overflowing_sub(Duration::hours(24)), (time, -1));
+        assert_eq!(Time::midnight().overflowing_sub(Duration::nanoseconds(1)),
+                   (Time::from_hms_nano(23, 59, 59, 999_999_999).unwrap(), -1));
+
+        assert_eq!(time + Duration::hours(2), Time::from_hms(1, 0, 0).unwrap());
+        assert_eq!(time - Duration::hours(24), time);
+        assert_eq!(time.wrapping_sub(Duration::hours(23)), Time::midnight());
+    }
+
+    #[test]
+    fn test_strftime() {
+        let time = Time::from_hms_nano(21, 5, 9, 54321).unwrap();
+        assert_eq!(time.strftime("%H:%M:%S.%f").unwrap().to_string(),
+                   "21:05:09.000054321".to_string());
+        assert_eq!(time.strftime("%I%p").unwrap().to_string(), "09PM".to_string());
+        assert_eq!(time.strftime("%R|%T %%").unwrap().to_string(),
+                   "21:05|21:05:09 %".to_string());
+        assert_eq!(time.to_string(), "21:05:09.000054321".to_string());
+        assert_eq!(Time::midnight().to_string(), "00:00:00".to_string());
+
+        assert_eq!(time.strftime("%Y").unwrap_err(), InvalidFormatSpecifier('Y'));
+        assert_eq!(time.strftime("%z").unwrap_err(), InvalidFormatSpecifier('z'));
+    }
+
+    #[test]
+    fn test_strptime() {
+        assert_eq!(Time::strptime("09:30", "%R"), Time::from_hms(9, 30, 0));
+        assert_eq!(Time::strptime("21:05:09.25", "%T.%f"),
+                   Time::from_hms_nano(21, 5, 9, 250_000_000));
+        assert_eq!(Time::strptime("09PM", "%I%p"), Time::from_hms(21, 0, 0));
+        assert_eq!(Time::strptime("12AM", "%I%p"), Ok(Time::midnight()));
+        assert_eq!(Time::strptime("24:00", "%H:%M"), Err(InvalidHour));
+        assert_eq!(Time::strptime("2015-01-01", "%Y-%m-%d"),
+                   Err(InvalidFormatSpecifier('Y')));
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Days of the week.
+
+use std::ascii::AsciiExt;
+use std::fmt;
+use std::str::FromStr;
+
+use self::Weekday::{Sunday, Monday, Tuesday, Wednesday, Thursday, Friday, Saturday};
+
+/// A day of the week.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Show)]
+pub enum Weekday {
+    Sunday,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+}
+
+/// Every day of the week, starting on Sunday like `Tm::tm_wday`.
+pub static WEEKDAYS: [Weekday; 7] = [Sunday, Monday, Tuesday, Wednesday, Thursday,
+                                     Friday, Saturday];
+
+impl Weekday {
+    /// Returns the day numbered `n` from Sunday, as in `Tm::tm_wday`: 0 is
+    /// Sunday and 6 is Saturday.
+    pub fn from_number(n: i32) -> Option<Weekday> {
+        if n >= 0 && n < 7 { Some(WEEKDAYS[n as usize]) } else { None }
+    }
+
+    /// Returns the day numbered `n` from Monday, as in ISO 8601: 1 is Monday
+    /// and 7 is Sunday.
+    pub fn from_iso_number(n: i32) -> Option<Weekday> {
+        if n >= 1 && n <= 7 { Some(WEEKDAYS[(n % 7) as usize]) } else { None }
+    }
+
+    /// Returns the number of days since Sunday, in `[0, 6]`, as in
+    /// `Tm::tm_wday`.
+    pub fn number(&self) -> i32 {
+        *self as i32
+    }
+
+    /// Returns the ISO 8601 number of the day, in `[1, 7]` with 1 being
+    /// Monday.
+    pub fn iso_number(&self) -> i32 {
+        match *self {
+            Sunday => 7,
+            day => day.number(),
+        }
+    }
+
+    /// Returns the following day, wrapping from Saturday to Sunday.
+    pub fn succ(&self) -> Weekday {
+        self.add_days(1)
+    }
+
+    /// Returns the previous day, wrapping from Sunday to Saturday.
+    pub fn pred(&self) -> Weekday {
+        self.add_days(-1)
+    }
+
+    /// Returns the day `days` days later, or earlier for negative `days`.
+    pub fn add_days(&self, days: i64) -> Weekday {
+        let n = (self.number() as i64 + days % 7 + 7) % 7;
+        WEEKDAYS[n as usize]
+    }
+
+    /// Returns the number of days from this day forward to `other`, in
+    /// `[0, 6]`.
+    pub fn days_until(&self, other: Weekday) -> i32 {
+        (other.number() - self.number() + 7) % 7
+    }
+
+    /// Returns the English name of the day, such as "Monday".
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Sunday => "Sunday",
+            Monday => "Monday",
+            Tuesday => "Tuesday",
+            Wednesday => "Wednesday",
+            Thursday => "Thursday",
+            Friday => "Friday",
+            Saturday => "Saturday",
+        }
+    }
+
+    /// Returns the three-letter English abbreviation of the day, such as
+    /// "Mon".
+    pub fn abbreviation(&self) -> &'static str {
+        self.name().slice_to(3)
+    }
+}
+
+impl FromStr for Weekday {
+    /// Parses the full or abbreviated English name of a day, ignoring ASCII
+    /// case.
+    fn from_str(s: &str) -> Option<Weekday> {
+        WEEKDAYS.iter().map(|&day| day).find(|day| {
+            s.eq_ignore_ascii_case(day.name()) || s.eq_ignore_ascii_case(day.abbreviation())
+        })
+    }
+}
+
+impl fmt::String for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Weekday, WEEKDAYS};
+    use super::Weekday::{Sunday, Monday, Wednesday, Saturday};
+
+    #[test]
+    fn test_numbers() {
+        for (i, &day) in WEEKDAYS.iter().enumerate() {
+            assert_eq!(day.number(), i as i32);
+            assert_eq!(Weekday::from_number(i as i32), Some(day));
+            assert_eq!(Weekday::from_iso_number(day.iso_number()), Some(day));
+        }
+        assert_eq!(Sunday.iso_number(), 7);
+        assert_eq!(Monday.iso_number(), 1);
+        assert_eq!(Weekday::from_number(7), None);
+        assert_eq!(Weekday::from_number(-1), None);
+        assert_eq!(Weekday::from_iso_number(0), None);
+        assert_eq!(Weekday::from_iso_number(7), Some(Sunday));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(Saturday.succ(), Sunday);
+        assert_eq!(Sunday.pred(), Saturday);
+        assert_eq!(Monday.succ().succ(), Wednesday);
+        assert_eq!(Monday.add_days(-8), Sunday);
+        assert_eq!(Monday.add_days(7 * 1000 + 2), Wednesday);
+        assert_eq!(Saturday.days_until(Monday), 2);
+        assert_eq!(Monday.days_until(Monday), 0);
+
+        let mut day = Sunday;
+        for &expected in WEEKDAYS.iter() {
+            assert_eq!(day, expected);
+            day = day.succ();
+        }
+        assert_eq!(day, Sunday);
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(Wednesday.name(), "Wednesday");
+        assert_eq!(Wednesday.abbreviation(), "Wed");
+        assert_eq!(Wednesday.to_string(), "Wednesday".to_string());
+
+        assert_eq!("Wednesday".parse::<Weekday>(), Some(Wednesday));
+        assert_eq!("wed".parse::<Weekday>(), Some(Wednesday));
+        assert_eq!("SATURDAY".parse::<Weekday>(), Some(Saturday));
+        assert_eq!("Wednes".parse::<Weekday>(), None);
+        assert_eq!("".parse::<Weekday>(), None);
+    }
+}
@@ -0,0 +1,917 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reading of compiled time zone information (TZif) files, as found in
+//! `/usr/share/zoneinfo`.
+//!
+//! The format is described in RFC 8536 and in `tzfile(5)`. Versions 1, 2 and
+//! 3 are understood; for version 2 and later files only the 64-bit data block
+//! is used, and local time after the last transition is computed from the
+//! POSIX TZ string in the footer. Leap second records are skipped, so the
+//! `right/` zones are not supported.
+
+use std::fmt;
+use std::i32;
+use std::i64;
+use std::io::File;
+use std::num::Int;
+use std::os;
+use std::path::Path;
+
+use super::{Timespec, Tm, ZonedTm, ParseError};
+use super::ParseError::InvalidYear;
+use calendar;
+use posix_tz::PosixTz;
+
+use self::ZoneError::{InvalidHeader, UnsupportedVersion, UnexpectedEof,
+                      InvalidTransition, InvalidLocalTimeType,
+                      InvalidAbbreviation, InvalidFooter, Io,
+                      UnknownZone, InvalidTzString};
+
+static MAGIC: &'static [u8] = b"TZif";
+
+/// The directory searched for named zones when `TZDIR` is not set.
+static DEFAULT_TZDIR: &'static str = "/usr/share/zoneinfo";
+
+/// A local time type of a time zone: an offset from UTC, whether it is
+/// daylight saving time, and its abbreviation.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct LocalTimeType {
+    /// The number of seconds east of UTC.
+    pub utcoff: i32,
+
+    /// Whether this local time type is daylight saving time.
+    pub isdst: bool,
+
+    /// The abbreviation of this local time type, such as "CEST".
+    pub abbr: String,
+}
+
+/// A change of the local time type of a time zone.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct Transition {
+    /// The instant at which `after` takes effect.
+    pub instant: Timespec,
+
+    /// The local time type in effect just before `instant`.
+    pub before: LocalTimeType,
+
+    /// The local time type in effect from `instant` on.
+    pub after: LocalTimeType,
+}
+
+/// An iterator over the transitions of a time zone, created by
+/// `TimeZone::transitions`.
+pub struct Transitions<'a> {
+    zone: &'a TimeZone,
+    sec: i64,
+    end: i64,
+}
+
+/// The result of looking up the instant at which a time zone's wall clock
+/// shows a given time.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum LocalResult<T> {
+    /// The wall-clock time was skipped by a transition, such as the start of
+    /// daylight saving time.
+    None,
+    /// The wall-clock time occurs exactly once.
+    Single(T),
+    /// The wall-clock time occurs twice, such as at the end of daylight saving
+    /// time. The earlier instant comes first.
+    Ambiguous(T, T),
+}
+
+impl<T> LocalResult<T> {
+    /// Returns the instant if it is unique.
+    pub fn single(self) -> Option<T> {
+        match self {
+            LocalResult::Single(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// Returns the earliest instant, if any.
+    pub fn earliest(self) -> Option<T> {
+        match self {
+            LocalResult::None => None,
+            LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => Some(t),
+        }
+    }
+
+    /// Returns the latest instant, if any.
+    pub fn latest(self) -> Option<T> {
+        match self {
+            LocalResult::None => None,
+            LocalResult::Single(t) | LocalResult::Ambiguous(_, t) => Some(t),
+        }
+    }
+}
+
+/// A policy for resolving wall-clock times that are skipped or repeated by a
+/// transition.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum Disambiguate {
+    /// Repeated times resolve to the earlier instant; skipped times are
+    /// rejected.
+    Earliest,
+    /// Repeated times resolve to the later instant; skipped times are
+    /// rejected.
+    Latest,
+    /// Repeated times resolve to the earlier instant; skipped times are moved
+    /// forward by the length of the gap, so that 02:30 becomes 03:30 when
+    /// clocks go forward from 02:00 to 03:00.
+    ShiftForward,
+    /// Both repeated and skipped times are rejected.
+    Error,
+}
+
+/// The rules of a time zone, read from a TZif file or given by a POSIX TZ
+/// string.
+#[derive(Clone, PartialEq, Show)]
+pub struct TimeZone {
+    /// Transition instants in seconds since the epoch, in ascending order.
+    transitions: Vec<i64>,
+
+    /// The index into `types` of the local time type starting at each
+    /// transition.
+    transition_types: Vec<usize>,
+
+    types: Vec<LocalTimeType>,
+
+    /// The rule for local time after the last transition.
+    rule: Option<PosixTz>,
+}
+
+#[derive(Copy, Clone, PartialEq, Show)]
+pub enum ZoneError {
+    InvalidHeader,
+    UnsupportedVersion(u8),
+    UnexpectedEof,
+    InvalidTransition,
+    InvalidLocalTimeType,
+    InvalidAbbreviation,
+    InvalidFooter,
+    Io,
+    UnknownZone,
+    InvalidTzString,
+}
+
+impl fmt::String for ZoneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InvalidHeader => write!(f, "Invalid TZif header."),
+            UnsupportedVersion(v) => write!(f, "Unsupported TZif version: {}", v),
+            UnexpectedEof => write!(f, "Unexpected end of TZif data."),
+            InvalidTransition => write!(f, "Invalid transition."),
+            InvalidLocalTimeType => write!(f, "Invalid local time type."),
+            InvalidAbbreviation => write!(f, "Invalid time zone abbreviation."),
+            InvalidFooter => write!(f, "Invalid TZif footer."),
+            Io => write!(f, "Could not read the time zone file."),
+            UnknownZone => write!(f, "Unknown time zone."),
+            InvalidTzString => write!(f, "Invalid POSIX TZ string."),
+        }
+    }
+}
+
+/// Returns the directory searched for named zones.
+pub fn tzdir() -> Path {
+    Path::new(os::getenv("TZDIR").unwrap_or(DEFAULT_TZDIR.to_string()))
+}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], ZoneError> {
+        if self.data.len() - self.pos < n {
+            return Err(UnexpectedEof);
+        }
+        let bytes = self.data.slice(self.pos, self.pos + n);
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ZoneError> {
+        self.read_bytes(1).map(|b| b[0])
+    }
+
+    fn read_be_u32(&mut self) -> Result<u32, ZoneError> {
+        let b = try!(self.read_bytes(4));
+        Ok((b[0] as u32) << 24 | (b[1] as u32) << 16 | (b[2] as u32) << 8 | b[3] as u32)
+    }
+
+    fn read_be_i32(&mut self) -> Result<i32, ZoneError> {
+        self.read_be_u32().map(|v| v as i32)
+    }
+
+    fn read_be_i64(&mut self) -> Result<i64, ZoneError> {
+        let hi = try!(self.read_be_u32()) as u64;
+        let lo = try!(self.read_be_u32()) as u64;
+        Ok((hi << 32 | lo) as i64)
+    }
+}
+
+struct Header {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+fn read_header(r: &mut Cursor) -> Result<Header, ZoneError> {
+    if try!(r.read_bytes(4)) != MAGIC {
+        return Err(InvalidHeader);
+    }
+    let version = match try!(r.read_u8()) {
+        0 => 1,
+        v @ b'2' ... b'4' => v - b'0',
+        v => return Err(UnsupportedVersion(v)),
+    };
+    try!(r.read_bytes(15));
+
+    let header = Header {
+        version: version,
+        isutcnt: try!(r.read_be_u32()) as usize,
+        isstdcnt: try!(r.read_be_u32()) as usize,
+        leapcnt: try!(r.read_be_u32()) as usize,
+        timecnt: try!(r.read_be_u32()) as usize,
+        typecnt: try!(r.read_be_u32()) as usize,
+        charcnt: try!(r.read_be_u32()) as usize,
+    };
+    if header.typecnt == 0 || header.charcnt == 0 ||
+       (header.isutcnt != 0 && header.isutcnt != header.typecnt) ||
+       (header.isstdcnt != 0 && header.isstdcnt != header.typecnt) {
+        return Err(InvalidHeader);
+    }
+    Ok(header)
+}
+
+impl Header {
+    /// Returns the length of the data block that follows the header when
+    /// transition times are `time_size` bytes wide, or `None` if it does not
+    /// fit in a `usize`.
+    fn block_len(&self, time_size: usize) -> Option<usize> {
+        let parts = [(self.timecnt, time_size + 1), (self.typecnt, 6), (self.charcnt, 1),
+                     (self.leapcnt, time_size + 4), (self.isstdcnt, 1), (self.isutcnt, 1)];
+        parts.iter().fold(Some(0), |len, &(count, size)| {
+            len.and_then(|len| count.checked_mul(size).and_then(|n| len.checked_add(n)))
+        })
+    }
+}
+
+/// Reads a data block whose transition times and leap second occurrences are
+/// `time_size` bytes wide.
+fn read_data_block(r: &mut Cursor, header: &Header, time_size: usize)
+                   -> Result<TimeZone, ZoneError> {
+    // Checking the length up front keeps a corrupt header from making us
+    // allocate for data that is not there.
+    match header.block_len(time_size) {
+        Some(len) if len <= r.data.len() - r.pos => (),
+        Some(_) => return Err(UnexpectedEof),
+        None => return Err(InvalidHeader),
+    }
+
+    let mut transitions = Vec::with_capacity(header.timecnt);
+    for _ in 0..header.timecnt {
+        let t = if time_size == 4 {
+            try!(r.read_be_i32()) as i64
+        } else {
+            try!(r.read_be_i64())
+        };
+        if transitions.last().map_or(false, |&last| last >= t) {
+            return Err(InvalidTransition);
+        }
+        transitions.push(t);
+    }
+
+    let mut transition_types = Vec::with_capacity(header.timecnt);
+    for _ in 0..header.timecnt {
+        let idx = try!(r.read_u8()) as usize;
+        if idx >= header.typecnt {
+            return Err(InvalidTransition);
+        }
+        transition_types.push(idx);
+    }
+
+    let mut raw_types = Vec::with_capacity(header.typecnt);
+    for _ in 0..header.typecnt {
+        let utcoff = try!(r.read_be_i32());
+        let isdst = try!(r.read_u8());
+        let abbr_idx = try!(r.read_u8()) as usize;
+        // -2**31 is disallowed so that the offset can always be negated.
+        if utcoff == i32::MIN || isdst > 1 || abbr_idx >= header.charcnt {
+            return Err(InvalidLocalTimeType);
+        }
+        raw_types.push((utcoff, isdst == 1, abbr_idx));
+    }
+
+    let chars = try!(r.read_bytes(header.charcnt));
+    let mut types = Vec::with_capacity(header.typecnt);
+    for &(utcoff, isdst, abbr_idx) in raw_types.iter() {
+        let rest = chars.slice_from(abbr_idx);
+        let len = match rest.iter().position(|&b| b == 0) {
+            Some(len) => len,
+            None => return Err(InvalidAbbreviation),
+        };
+        let abbr = match String::from_utf8(rest.slice_to(len).to_vec()) {
+            Ok(abbr) => abbr,
+            Err(..) => return Err(InvalidAbbreviation),
+        };
+        types.push(LocalTimeType { utcoff: utcoff, isdst: isdst, abbr: abbr });
+    }
+
+    // Leap second records and the standard/wall and UT/local indicators are
+    // not needed for conversions.
+    let leap_len = try!(header.leapcnt.checked_mul(time_size + 4).ok_or(InvalidHeader));
+    try!(r.read_bytes(leap_len));
+    try!(r.read_bytes(header.isstdcnt));
+    try!(r.read_bytes(header.isutcnt));
+
+    Ok(TimeZone {
+        transitions: transitions,
+        transition_types: transition_types,
+        types: types,
+        rule: None,
+    })
+}
+
+fn read_footer(r: &mut Cursor) -> Result<Option<PosixTz>, ZoneError> {
+    let rest = try!(r.read_bytes(r.data.len() - r.pos));
+    if rest.len() < 2 || rest[0] != b'\n' {
+        return Err(InvalidFooter);
+    }
+    let len = match rest.slice_from(1).iter().position(|&b| b == b'\n') {
+        Some(len) => len,
+        None => return Err(InvalidFooter),
+    };
+    if len == 0 {
+        return Ok(None);
+    }
+    let footer = match String::from_utf8(rest.slice(1, len + 1).to_vec()) {
+        Ok(footer) => footer,
+        Err(..) => return Err(InvalidFooter),
+    };
+    match PosixTz::parse(footer.as_slice()) {
+        Ok(rule) => Ok(Some(rule)),
+        Err(..) => Err(InvalidFooter),
+    }
+}
+
+impl TimeZone {
+    /// Parses the contents of a TZif file.
+    pub fn from_bytes(data: &[u8]) -> Result<TimeZone, ZoneError> {
+        let mut r = Cursor { data: data, pos: 0 };
+        let header = try!(read_header(&mut r));
+        let v1 = try!(read_data_block(&mut r, &header, 4));
+        if header.version < 2 {
+            return Ok(v1);
+        }
+
+        let header = try!(read_header(&mut r));
+        let mut zone = try!(read_data_block(&mut r, &header, 8));
+        zone.rule = try!(read_footer(&mut r));
+        Ok(zone)
+    }
+
+    /// Creates a zone from a POSIX TZ string, such as
+    /// "CET-1CEST,M3.5.0,M10.5.0/3".
+    pub fn from_tz_string(s: &str) -> Result<TimeZone, ZoneError> {
+        let rule = try!(PosixTz::parse(s));
+        Ok(TimeZone {
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            types: vec![rule.local_time_type(0).clone()],
+            rule: Some(rule),
+        })
+    }
+
+    /// Returns the UTC time zone.
+    pub fn utc() -> TimeZone {
+        TimeZone {
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            types: vec![LocalTimeType { utcoff: 0, isdst: false, abbr: "UTC".to_string() }],
+            rule: None,
+        }
+    }
+
+    /// Returns a zone whose local time is always `utcoff` seconds east of
+    /// UTC, such as the offset of a `Tm` from `at_offset` or `strptime`. Its
+    /// abbreviation is the offset as `%z` prints it.
+    pub fn fixed(utcoff: i32) -> TimeZone {
+        let sign = if utcoff < 0 { '-' } else { '+' };
+        let minutes = (utcoff as i64).abs() / 60;
+        TimeZone {
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            types: vec![LocalTimeType {
+                utcoff: utcoff,
+                isdst: false,
+                abbr: format!("{}{:02}{:02}", sign, minutes / 60, minutes % 60),
+            }],
+            rule: None,
+        }
+    }
+
+    /// Reads and parses the TZif file at `path`.
+    pub fn from_file(path: &Path) -> Result<TimeZone, ZoneError> {
+        match File::open(path).and_then(|mut f| f.read_to_end()) {
+            Ok(data) => TimeZone::from_bytes(data.as_slice()),
+            Err(..) => Err(Io),
+        }
+    }
+
+    /// Loads the zone with the given IANA name, such as "Europe/Berlin", from
+    /// the directory named by the `TZDIR` environment variable, or from
+    /// `/usr/share/zoneinfo` if it is not set.
+    pub fn named(name: &str) -> Result<TimeZone, ZoneError> {
+        TimeZone::named_in(&tzdir(), name)
+    }
+
+    /// Loads the zone with the given IANA name from the zoneinfo directory
+    /// `dir`.
+    pub fn named_in(dir: &Path, name: &str) -> Result<TimeZone, ZoneError> {
+        // Only accept names that stay inside the zoneinfo directory.
+        if name.is_empty() || name.starts_with("/") ||
+           name.split('/').any(|part| part.is_empty() || part == "." || part == "..") {
+            return Err(UnknownZone);
+        }
+        match TimeZone::from_file(&dir.join(name)) {
+            Err(Io) => Err(UnknownZone),
+            result => result,
+        }
+    }
+
+    /// Returns the local time type in effect at `sec` seconds since the
+    /// epoch.
+    pub fn local_time_type(&self, sec: i64) -> &LocalTimeType {
+        // After the last transition, the footer rule applies if there is one.
+        if self.transitions.last().map_or(true, |&last| sec >= last) {
+            if let Some(ref rule) = self.rule {
+                return rule.local_time_type(sec);
+            }
+        }
+
+        // Before the first transition, and in zones without transitions,
+        // local time is given by the first local time type.
+        if self.transitions.is_empty() || sec < self.transitions[0] {
+            return &self.types[0];
+        }
+
+        // Find the last transition at or before `sec`.
+        let mut lo = 0;
+        let mut hi = self.transitions.len();
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if self.transitions[mid] <= sec { lo = mid } else { hi = mid }
+        }
+        &self.types[self.transition_types[lo]]
+    }
+
+    /// Returns an iterator over the transitions of this zone at or after
+    /// `from` and before `to`, in ascending order.
+    pub fn transitions(&self, from: Timespec, to: Timespec) -> Transitions {
+        Transitions {
+            zone: self,
+            sec: if from.nsec > 0 { from.sec.saturating_add(1) } else { from.sec },
+            end: if to.nsec > 0 { to.sec.saturating_add(1) } else { to.sec },
+        }
+    }
+
+    /// Returns the first transition of this zone after `after`, if any.
+    pub fn next_transition(&self, after: Timespec) -> Option<Transition> {
+        after.sec.checked_add(1).and_then(|sec| {
+            self.transitions(Timespec::new(sec, 0), Timespec::new(i64::MAX, 0)).next()
+        })
+    }
+
+    /// Returns the first instant at or after `sec` seconds since the epoch at
+    /// which the transition table or the footer rule may change local time.
+    fn next_transition_instant(&self, sec: i64) -> Option<i64> {
+        // Find the first transition at or after `sec`.
+        let mut lo = 0;
+        let mut hi = self.transitions.len();
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.transitions[mid] < sec { lo = mid + 1 } else { hi = mid }
+        }
+        if lo < self.transitions.len() {
+            return Some(self.transitions[lo]);
+        }
+
+        // The footer rule only applies after the last transition.
+        self.rule.as_ref().and_then(|rule| {
+            match self.transitions.last() {
+                Some(&last) if last >= sec => {
+                    last.checked_add(1).and_then(|sec| rule.next_transition(sec))
+                }
+                _ => rule.next_transition(sec),
+            }
+        })
+    }
+
+    /// Returns the instants at which the wall clock of this zone shows the
+    /// time in `tm`. `tm_wday`, `tm_yday`, `tm_isdst` and `tm_utcoff` are
+    /// ignored.
+    pub fn local_to_timespec(&self, tm: &Tm) -> LocalResult<Timespec> {
+        let local = calendar::local_seconds(tm);
+        let mut found: Vec<i64> = Vec::new();
+        for &utcoff in self.nearby_offsets(local).iter() {
+            let sec = local - utcoff as i64;
+            if self.local_time_type(sec).utcoff == utcoff && !found.contains(&sec) {
+                found.push(sec);
+            }
+        }
+        found.sort();
+
+        match found.len() {
+            0 => LocalResult::None,
+            1 => LocalResult::Single(Timespec::new(found[0], tm.tm_nsec)),
+            n => LocalResult::Ambiguous(Timespec::new(found[0], tm.tm_nsec),
+                                        Timespec::new(found[n - 1], tm.tm_nsec)),
+        }
+    }
+
+    /// Returns the instant at which the wall clock of this zone shows the time
+    /// in `tm`, resolving skipped and repeated times according to `policy`.
+    /// Returns `None` if `policy` rejects the time.
+    pub fn local_to_timespec_with(&self, tm: &Tm, policy: Disambiguate)
+                                  -> Option<Timespec> {
+        match (self.local_to_timespec(tm), policy) {
+            (LocalResult::Single(t), _) => Some(t),
+            (LocalResult::Ambiguous(t, _), Disambiguate::Earliest) |
+            (LocalResult::Ambiguous(t, _), Disambiguate::ShiftForward) => Some(t),
+            (LocalResult::Ambiguous(_, t), Disambiguate::Latest) => Some(t),
+            (LocalResult::None, Disambiguate::ShiftForward) => {
+                Some(Timespec::new(self.shift_forward(calendar::local_seconds(tm)), tm.tm_nsec))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the UTC offsets in effect within two days of `local`, which
+    /// includes every offset that `local` could have been computed with.
+    fn nearby_offsets(&self, local: i64) -> Vec<i32> {
+        let window = 2 * calendar::SECS_PER_DAY;
+        let mut offsets = vec![self.local_time_type(local - window).utcoff];
+        for t in self.transitions(Timespec::new(local - window, 0),
+                                  Timespec::new(local + window, 0)) {
+            offsets.push(t.after.utcoff);
+        }
+        offsets
+    }
+
+    /// Reads the skipped wall-clock time `local` with the offset in effect
+    /// before the gap, which moves it forward by the length of the gap.
+    fn shift_forward(&self, local: i64) -> i64 {
+        let window = 2 * calendar::SECS_PER_DAY;
+        for t in self.transitions(Timespec::new(local - window, 0),
+                                  Timespec::new(local + window, 0)) {
+            let before = local - t.before.utcoff as i64;
+            let after = local - t.after.utcoff as i64;
+            if after < t.instant.sec && t.instant.sec <= before {
+                return before;
+            }
+        }
+        local - self.local_time_type(local).utcoff as i64
+    }
+
+    /// Returns the specified time in this time zone.
+    ///
+    /// Panics if the year is outside `[MIN_YEAR, MAX_YEAR]`.
+    pub fn at(&self, clock: Timespec) -> Tm {
+        self.try_at(clock).ok().expect("year out of the supported range")
+    }
+
+    /// Returns the specified time in this time zone, or `Err(InvalidYear)`
+    /// if the year is outside `[MIN_YEAR, MAX_YEAR]`.
+    pub fn try_at(&self, clock: Timespec) -> Result<Tm, ParseError> {
+        if !calendar::in_supported_range(clock.sec) {
+            return Err(InvalidYear);
+        }
+        let ty = self.local_time_type(clock.sec);
+        let mut tm = try!(calendar::checked_tm_from_seconds(clock.sec, clock.nsec, ty.utcoff)
+                              .ok_or(InvalidYear));
+        tm.tm_isdst = if ty.isdst { 1 } else { 0 };
+        Ok(tm)
+    }
+
+    /// Returns the specified time in this time zone, together with the
+    /// abbreviation of the local time type in effect.
+    pub fn at_zoned(&self, clock: Timespec) -> ZonedTm {
+        ZonedTm {
+            tm: self.at(clock),
+            abbr: self.local_time_type(clock.sec).abbr.clone(),
+        }
+    }
+}
+
+impl<'a> Iterator for Transitions<'a> {
+    type Item = Transition;
+
+    fn next(&mut self) -> Option<Transition> {
+        while self.sec < self.end {
+            let t = match self.zone.next_transition_instant(self.sec) {
+                Some(t) if t < self.end => t,
+                _ => break,
+            };
+            self.sec = t + 1;
+
+            // Transitions that only repeat the current local time type are
+            // skipped, as is one at the first representable second.
+            let before = self.zone.local_time_type(t.saturating_sub(1));
+            let after = self.zone.local_time_type(t);
+            if before != after {
+                return Some(Transition {
+                    instant: Timespec::new(t, 0),
+                    before: before.clone(),
+                    after: after.clone(),
+                });
+            }
+        }
+        self.sec = self.end;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TimeZone, LocalTimeType, Transition, LocalResult};
+    use super::Disambiguate::{Earliest, Latest, ShiftForward, Error};
+    use posix_tz::PosixTz;
+    use super::ZoneError::{InvalidHeader, UnexpectedEof, UnsupportedVersion,
+                           UnknownZone, InvalidFooter, InvalidTzString};
+    use std::i64;
+    use std::path::Path;
+    use {Timespec, strptime};
+
+    static BERLIN: &'static [u8] = include_bytes!("../tests/zoneinfo/Europe/Berlin");
+    static BERLIN_V1: &'static [u8] = include_bytes!("../tests/zoneinfo/Europe/Berlin.v1");
+    static LOS_ANGELES: &'static [u8] =
+        include_bytes!("../tests/zoneinfo/America/Los_Angeles");
+    static JERUSALEM: &'static [u8] = include_bytes!("../tests/zoneinfo/Asia/Jerusalem");
+    static UTC: &'static [u8] = include_bytes!("../tests/zoneinfo/UTC");
+
+    #[test]
+    fn test_parse_versions() {
+        let v1 = TimeZone::from_bytes(BERLIN_V1).unwrap();
+        assert!(v1.rule.is_none());
+
+        let v2 = TimeZone::from_bytes(BERLIN).unwrap();
+        assert_eq!(v2.rule, PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").ok());
+
+        let v3 = TimeZone::from_bytes(JERUSALEM).unwrap();
+        assert_eq!(v3.rule, PosixTz::parse("IST-2IDT,M3.4.4/26,M10.5.0").ok());
+
+        let utc = TimeZone::from_bytes(UTC).unwrap();
+        assert!(utc.transitions.is_empty());
+        assert_eq!(utc.rule, PosixTz::parse("UTC0").ok());
+        assert_eq!(utc.local_time_type(0),
+                   &LocalTimeType { utcoff: 0, isdst: false, abbr: "UTC".to_string() });
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(TimeZone::from_bytes(b""), Err(UnexpectedEof));
+        assert_eq!(TimeZone::from_bytes(b"TZjf2"), Err(InvalidHeader));
+        assert_eq!(TimeZone::from_bytes(BERLIN.slice_to(100)), Err(UnexpectedEof));
+
+        // A leap second count too large for the data.
+        let mut data = BERLIN_V1.to_vec();
+        data[29] = 0x10;
+        assert_eq!(TimeZone::from_bytes(data.as_slice()), Err(UnexpectedEof));
+
+        let mut data = BERLIN.to_vec();
+        data[4] = b'1';
+        assert_eq!(TimeZone::from_bytes(data.as_slice()), Err(UnsupportedVersion(b'1')));
+
+        let mut data = BERLIN.to_vec();
+        let len = data.len();
+        data[len - 2] = b'x';
+        assert_eq!(TimeZone::from_bytes(data.as_slice()), Err(InvalidFooter));
+        assert_eq!(TimeZone::from_tz_string("CET"), Err(InvalidTzString));
+    }
+
+    #[test]
+    fn test_named() {
+        let dir = Path::new("tests/zoneinfo");
+        assert_eq!(TimeZone::named_in(&dir, "Europe/Berlin"), TimeZone::from_bytes(BERLIN));
+        assert_eq!(TimeZone::named_in(&dir, "America/Los_Angeles"),
+                   TimeZone::from_bytes(LOS_ANGELES));
+
+        assert_eq!(TimeZone::named_in(&dir, "Europe/Atlantis"), Err(UnknownZone));
+        assert_eq!(TimeZone::named_in(&dir, ""), Err(UnknownZone));
+        assert_eq!(TimeZone::named_in(&dir, "Europe"), Err(UnknownZone));
+        assert_eq!(TimeZone::named_in(&dir, "/Europe/Berlin"), Err(UnknownZone));
+        assert_eq!(TimeZone::named_in(&dir, "Europe/../Europe/Berlin"), Err(UnknownZone));
+        assert_eq!(TimeZone::named_in(&dir, "Europe//Berlin"), Err(UnknownZone));
+    }
+
+    #[test]
+    fn test_at() {
+        let berlin = TimeZone::from_bytes(BERLIN).unwrap();
+        let berlin_v1 = TimeZone::from_bytes(BERLIN_V1).unwrap();
+
+        // 2009-02-13T23:31:30Z
+        for zone in [&berlin, &berlin_v1].iter() {
+            let tm = zone.at(Timespec::new(1234567890, 54321));
+            assert_eq!((tm.tm_year, tm.tm_mon, tm.tm_mday), (109, 1, 14));
+            assert_eq!((tm.tm_hour, tm.tm_min, tm.tm_sec), (0, 31, 30));
+            assert_eq!(tm.tm_wday, 6);
+            assert_eq!(tm.tm_yday, 44);
+            assert_eq!(tm.tm_isdst, 0);
+            assert_eq!(tm.tm_utcoff, 3600);
+            assert_eq!(tm.tm_nsec, 54321);
+        }
+
+        // 2014-07-01T12:00:00Z
+        let tm = berlin.at(Timespec::new(1404216000, 0));
+        assert_eq!(tm.tm_hour, 14);
+        assert_eq!(tm.tm_isdst, 1);
+        assert_eq!(tm.tm_utcoff, 7200);
+        assert_eq!(berlin.local_time_type(1404216000).abbr, "CEST".to_string());
+
+        let zoned = berlin.at_zoned(Timespec::new(1404216000, 0));
+        assert_eq!(zoned.tm, tm);
+        assert_eq!(zoned.abbr, "CEST".to_string());
+        assert_eq!(zoned.strftime("%H:%M %Z").unwrap().to_string(), "14:00 CEST".to_string());
+        assert_eq!(zoned.rfc822().to_string(), "Tue, 01 Jul 2014 14:00:00 CEST".to_string());
+
+        // Berlin switched to CEST at 2014-03-30T01:00:00Z.
+        assert_eq!(berlin.at(Timespec::new(1396141199, 0)).tm_utcoff, 3600);
+        assert_eq!(berlin.at(Timespec::new(1396141200, 0)).tm_utcoff, 7200);
+
+        let los_angeles = TimeZone::from_bytes(LOS_ANGELES).unwrap();
+        let tm = los_angeles.at(Timespec::new(1234567890, 0));
+        assert_eq!((tm.tm_mday, tm.tm_hour), (13, 15));
+        assert_eq!(tm.tm_utcoff, -28800);
+        assert_eq!(los_angeles.local_time_type(1234567890).abbr, "PST".to_string());
+
+        // Before the first transition, local mean time applies.
+        let lmt = los_angeles.local_time_type(-5000000000);
+        assert_eq!(lmt.utcoff, -28378);
+        assert_eq!(lmt.abbr, "LMT".to_string());
+
+        // 2020-07-01T00:00:00Z
+        let jerusalem = TimeZone::from_bytes(JERUSALEM).unwrap();
+        let tm = jerusalem.at(Timespec::new(1593561600, 0));
+        assert_eq!(tm.tm_utcoff, 10800);
+        assert_eq!(tm.tm_isdst, 1);
+
+        // After the last transition in 2037 the footer rule is used:
+        // 2100-07-01T00:00:00Z and 2100-12-01T00:00:00Z.
+        let tm = berlin.at(Timespec::new(4118083200, 0));
+        assert_eq!((tm.tm_hour, tm.tm_utcoff, tm.tm_isdst), (2, 7200, 1));
+        let tm = berlin.at(Timespec::new(4131302400, 0));
+        assert_eq!((tm.tm_hour, tm.tm_utcoff, tm.tm_isdst), (1, 3600, 0));
+        // Version 1 files have no footer and keep the last offset.
+        assert_eq!(berlin_v1.at(Timespec::new(4118083200, 0)).tm_utcoff, 3600);
+
+        let zoned = TimeZone::fixed(19800).at_zoned(Timespec::new(1234567890, 0));
+        assert_eq!((zoned.tm.tm_mday, zoned.tm.tm_hour, zoned.tm.tm_utcoff), (14, 5, 19800));
+        assert_eq!(zoned.abbr, "+0530".to_string());
+        assert_eq!(TimeZone::fixed(-28800).local_time_type(0).abbr, "-0800".to_string());
+        assert_eq!(TimeZone::fixed(0).local_time_type(0).abbr, "+0000".to_string());
+    }
+
+    #[test]
+    fn test_from_tz_string() {
+        let zone = TimeZone::from_tz_string("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let berlin = TimeZone::from_bytes(BERLIN).unwrap();
+        for &sec in [1234567890, 1404216000, 1396141199, 1396141200, 4118083200].iter() {
+            assert_eq!(zone.at(Timespec::new(sec, 0)), berlin.at(Timespec::new(sec, 0)));
+        }
+    }
+
+    #[test]
+    fn test_transitions() {
+        let berlin = TimeZone::from_bytes(BERLIN).unwrap();
+        let cet = LocalTimeType { utcoff: 3600, isdst: false, abbr: "CET".to_string() };
+        let cest = LocalTimeType { utcoff: 7200, isdst: true, abbr: "CEST".to_string() };
+
+        // 2014-01-01T00:00:00Z to 2015-01-01T00:00:00Z
+        let transitions: Vec<Transition> = berlin.transitions(Timespec::new(1388534400, 0),
+                                                              Timespec::new(1420070400, 0))
+                                                 .collect();
+        assert_eq!(transitions, vec![
+            Transition { instant: Timespec::new(1396141200, 0),
+                         before: cet.clone(), after: cest.clone() },
+            Transition { instant: Timespec::new(1414285200, 0),
+                         before: cest.clone(), after: cet.clone() },
+        ]);
+
+        // The range includes `from` but not `to`.
+        assert_eq!(berlin.transitions(Timespec::new(1396141200, 0),
+                                      Timespec::new(1414285200, 0)).count(), 1);
+        assert_eq!(berlin.transitions(Timespec::new(1396141200, 1),
+                                      Timespec::new(1414285200, 1)).count(), 1);
+
+        // From the end of the table in 2037 into the footer rule in 2038:
+        // 2037-01-01T00:00:00Z to 2039-01-01T00:00:00Z
+        let instants: Vec<i64> = berlin.transitions(Timespec::new(2114380800, 0),
+                                                    Timespec::new(2177452800, 0))
+                                       .map(|t| t.instant.sec).collect();
+        assert_eq!(instants, vec![2121901200, 2140045200, 2153350800, 2172099600]);
+
+        let next = berlin.next_transition(Timespec::new(1396141200, 0)).unwrap();
+        assert_eq!(next.instant, Timespec::new(1414285200, 0));
+        assert_eq!(berlin.next_transition(Timespec::new(1396141199, 999_999_999)).unwrap()
+                         .instant, Timespec::new(1396141200, 0));
+        assert!(berlin.next_transition(Timespec::new(4118083200, 0)).is_some());
+        assert!(berlin.next_transition(Timespec::new(i64::MAX, 0)).is_none());
+        assert!(berlin.next_transition(Timespec::new(i64::MAX - 1, 0)).is_none());
+        assert!(berlin.transitions(Timespec::new(i64::MAX, 1), Timespec::new(i64::MAX, 1))
+                      .next().is_none());
+
+        // The first transition of Los Angeles, from local mean time.
+        let los_angeles = TimeZone::from_bytes(LOS_ANGELES).unwrap();
+        let first = los_angeles.next_transition(Timespec::new(-5000000000, 0)).unwrap();
+        assert_eq!(first.instant, Timespec::new(-2717640000, 0));
+        assert_eq!(first.before.abbr, "LMT".to_string());
+        assert_eq!(first.after.abbr, "PST".to_string());
+
+        let berlin_v1 = TimeZone::from_bytes(BERLIN_V1).unwrap();
+        assert!(berlin_v1.next_transition(Timespec::new(4118083200, 0)).is_none());
+
+        let utc = TimeZone::from_bytes(UTC).unwrap();
+        assert!(utc.next_transition(Timespec::new(0, 0)).is_none());
+        assert!(TimeZone::utc().next_transition(Timespec::new(0, 0)).is_none());
+        assert!(TimeZone::fixed(19800).next_transition(Timespec::new(0, 0)).is_none());
+
+        // A transition at the first representable second has nothing before
+        // it.
+        let zone = TimeZone {
+            transitions: vec![i64::MIN, 0],
+            transition_types: vec![1, 0],
+            types: vec![cet.clone(), cest.clone()],
+            rule: None,
+        };
+        let instants: Vec<i64> = zone.transitions(Timespec::new(i64::MIN, 0),
+                                                  Timespec::new(i64::MAX, 0))
+                                     .map(|t| t.instant.sec).collect();
+        assert_eq!(instants, vec![0]);
+    }
+
+    #[test]
+    fn test_local_to_timespec() {
+        let los_angeles = TimeZone::from_bytes(LOS_ANGELES).unwrap();
+        let format = "%Y-%m-%d %H:%M:%S";
+
+        let tm = strptime("2009-02-13 15:31:30", format).unwrap();
+        let time = Timespec::new(1234567890, 0);
+        assert_eq!(los_angeles.local_to_timespec(&tm), LocalResult::Single(time));
+        for &policy in [Earliest, Latest, ShiftForward, Error].iter() {
+            assert_eq!(los_angeles.local_to_timespec_with(&tm, policy), Some(time));
+        }
+
+        // Clocks went forward from 02:00 PST to 03:00 PDT on 2014-03-09.
+        let tm = strptime("2014-03-09 02:30:00", format).unwrap();
+        assert_eq!(los_angeles.local_to_timespec(&tm), LocalResult::None);
+        assert_eq!(los_angeles.local_to_timespec_with(&tm, Earliest), None);
+        assert_eq!(los_angeles.local_to_timespec_with(&tm, Latest), None);
+        assert_eq!(los_angeles.local_to_timespec_with(&tm, Error), None);
+        assert_eq!(los_angeles.local_to_timespec_with(&tm, ShiftForward),
+                   Some(Timespec::new(1394361000, 0)));
+        let tm = strptime("2014-03-09 01:59:59", format).unwrap();
+        assert_eq!(los_angeles.local_to_timespec(&tm),
+                   LocalResult::Single(Timespec::new(1394359199, 0)));
+        let tm = strptime("2014-03-09 03:00:00", format).unwrap();
+        assert_eq!(los_angeles.local_to_timespec(&tm),
+                   LocalResult::Single(Timespec::new(1394359200, 0)));
+
+        // Clocks went back from 02:00 PDT to 01:00 PST on 2014-11-02.
+        let mut tm = strptime("2014-11-02 01:30:00", format).unwrap();
+        tm.tm_nsec = 5;
+        let (earlier, later) = (Timespec::new(1414917000, 5), Timespec::new(1414920600, 5));
+        assert_eq!(los_angeles.local_to_timespec(&tm), LocalResult::Ambiguous(earlier, later));
+        assert_eq!(los_angeles.local_to_timespec_with(&tm, Earliest), Some(earlier));
+        assert_eq!(los_angeles.local_to_timespec_with(&tm, Latest), Some(later));
+        assert_eq!(los_angeles.local_to_timespec_with(&tm, ShiftForward), Some(earlier));
+        assert_eq!(los_angeles.local_to_timespec_with(&tm, Error), None);
+
+        // Daylight saving time in Lord Howe is half an hour.
+        let lord_howe = TimeZone::from_tz_string("<+1030>-10:30<+11>-11,M10.1.0,M4.1.0").unwrap();
+        let tm = strptime("2014-10-05 02:15:00", format).unwrap();
+        assert_eq!(lord_howe.local_to_timespec(&tm), LocalResult::None);
+        let shifted = lord_howe.local_to_timespec_with(&tm, ShiftForward).unwrap();
+        assert_eq!((lord_howe.at(shifted).tm_hour, lord_howe.at(shifted).tm_min), (2, 45));
+        let tm = strptime("2015-04-05 01:45:00", format).unwrap();
+        match lord_howe.local_to_timespec(&tm) {
+            LocalResult::Ambiguous(a, b) => assert_eq!(b.sec - a.sec, 1800),
+            r => panic!("{:?}", r),
+        }
+    }
+}